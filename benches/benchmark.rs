@@ -1,7 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use igc::records::Record;
 
-fn parse_records(s: &str) -> Vec<Record> {
+fn parse_records(s: &str) -> Vec<Record<'_>> {
     s.lines()
         .map(|line| Record::parse_line(line).unwrap())
         .collect::<Vec<_>>()
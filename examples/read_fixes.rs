@@ -1,27 +1,15 @@
-use igc::records::Record;
-use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
+use igc::records::fixes;
+use std::fs;
 
 fn main() {
     let filename = "examples/example.igc";
 
-    let file = File::open(filename).unwrap();
-    let reader = BufReader::new(&file);
+    let text = match fs::read_to_string(filename) {
+        Ok(text) => text,
+        Err(_) => std::process::exit(-1),
+    };
 
-    for result in reader.lines() {
-        let line = match result {
-            Ok(line) => line,
-            Err(_) => std::process::exit(-1),
-        };
-
-        let record = match Record::parse_line(&line) {
-            Ok(record) => record,
-            Err(_) => std::process::exit(-1),
-        };
-
-        if let Record::B(b_rec) = record {
-            println!("b_rec = {:?}", b_rec);
-        }
+    for b_rec in fixes(&text) {
+        println!("b_rec = {:?}", b_rec);
     }
 }
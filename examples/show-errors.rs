@@ -1,12 +1,9 @@
-use std::borrow::Cow;
 use std::env;
 use std::fs;
 use std::path;
 
-use encoding::all::{ISO_8859_1, UTF_8};
-use encoding::{DecoderTrap, Encoding};
-
-use igc::records::Record;
+use igc::records::{split_lines, RecordReader};
+use igc::util::{decode_igc_bytes, detect_encoding, Encoding};
 
 fn main() {
     // collect command line arguments
@@ -28,29 +25,26 @@ fn main() {
 
         // open file in buffered reader
         let bytes = fs::read(&path).unwrap();
-        let text = match as_text(&bytes) {
-            Err(error) => {
-                println!("{} ERROR {}", filename, error);
-                continue;
+        if detect_encoding(&bytes) == Encoding::Unknown {
+            println!("{} ERROR unknown text encoding", filename);
+            continue;
+        }
+        let text = decode_igc_bytes(&bytes);
+
+        let mut reader = RecordReader::new(&text);
+        while let Some(result) = reader.next_located() {
+            if let Err(error) = result {
+                let line = split_lines(&text).nth(error.line - 1).unwrap();
+                println!(
+                    "{}:{} ERROR {}: {}",
+                    filename, error.line, error.source, line
+                );
             }
-            Ok(text) => text,
-        };
-
-        for (i, line) in text.lines().enumerate() {
-            let line_number = i + 1;
-
-            match Record::parse_line(&line) {
-                Err(error) => {
-                    println!("{}:{} ERROR {:?}: {}", filename, line_number, error, line);
-                    continue;
-                }
-                Ok(_) => {}
-            };
         }
     }
 }
 
-fn is_igc_file(path: &path::PathBuf) -> bool {
+fn is_igc_file(path: &path::Path) -> bool {
     match path.extension() {
         None => false,
         Some(os_str) => match os_str.to_str() {
@@ -59,10 +53,3 @@ fn is_igc_file(path: &path::PathBuf) -> bool {
         },
     }
 }
-
-pub fn as_text(bytes: &[u8]) -> Result<String, Cow<str>> {
-    let bytes = bytes.into();
-    UTF_8
-        .decode(bytes, DecoderTrap::Strict)
-        .or_else(|_| ISO_8859_1.decode(bytes, DecoderTrap::Strict))
-}
@@ -0,0 +1,1254 @@
+//! Higher level analysis of the fixes making up a flight trace
+
+use crate::records::{AltitudeSource, BRecord, BRecordBuf, Extension};
+use crate::util::{MonotonicTime, Time};
+
+/// Number of seconds elapsed between two fixes, assuming `b` was recorded after `a`.
+///
+/// Crossing midnight is handled as by `MonotonicTime`, which also treats `b` being a little
+/// earlier than `a` as no time having passed.
+fn seconds_between(a: &BRecord, b: &BRecord) -> u32 {
+    let mut time = MonotonicTime::new();
+    time.push(a.timestamp);
+    time.push(b.timestamp)
+}
+
+/// Each pair of consecutive fixes, along with the number of seconds between them as counted by
+/// a `MonotonicTime` over the whole sequence.
+fn timed_pairs<'f, 'a>(
+    fixes: &'f [BRecord<'a>],
+) -> impl Iterator<Item = (&'f BRecord<'a>, &'f BRecord<'a>, u32)> + 'f {
+    let mut time = MonotonicTime::new();
+    let mut last = None;
+    fixes.iter().filter_map(move |fix| {
+        let elapsed = time.push(fix.timestamp);
+        let (previous, previous_elapsed) = last.replace((fix, elapsed))?;
+        Some((previous, fix, elapsed - previous_elapsed))
+    })
+}
+
+/// Returns true if getting from fix `a` to fix `b` implies a ground speed greater than
+/// `max_speed_mps` (meters per second).
+///
+/// IGC timestamps only have a resolution of one second, so two fixes sharing a timestamp are
+/// treated as being one second apart rather than dividing by zero.
+///
+/// ```
+/// # use igc::{analysis::is_teleport, records::BRecord};
+/// let a = BRecord::parse("B0941145152265N00032642WA0011500115").unwrap();
+/// let b = BRecord::parse("B0941155252265N00032642WA0011500115").unwrap();
+/// assert!(is_teleport(&a, &b, 100.));
+/// ```
+pub fn is_teleport(a: &BRecord, b: &BRecord, max_speed_mps: f64) -> bool {
+    let dt = seconds_between(a, b).max(1);
+    a.pos.haversine_distance_m(&b.pos) > max_speed_mps * f64::from(dt)
+}
+
+/// Total great circle length in meters of the path through a sequence of fixes.
+///
+/// If `max_speed_mps` is given, any fix which would be a teleport (see `is_teleport`) from the
+/// last fix counted is treated as an outlier and skipped.
+///
+/// ```
+/// # use igc::{analysis::track_length, records::BRecord};
+/// let fixes = [
+///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+///     BRecord::parse("B1201015252265N00032642WA0100001000").unwrap(),
+///     BRecord::parse("B1201045152358N00032642WA0100001000").unwrap(),
+/// ];
+/// assert!((track_length(&fixes, Some(70.)) - 172.).abs() < 1.);
+/// ```
+pub fn track_length(fixes: &[BRecord], max_speed_mps: Option<f64>) -> f64 {
+    let mut fixes = fixes.iter();
+    let mut last = match fixes.next() {
+        Some(fix) => fix,
+        None => return 0.,
+    };
+
+    let mut length = 0.;
+    for fix in fixes {
+        if let Some(max_speed_mps) = max_speed_mps {
+            if is_teleport(last, fix, max_speed_mps) {
+                continue;
+            }
+        }
+
+        length += last.pos.haversine_distance_m(&fix.pos);
+        last = fix;
+    }
+    length
+}
+
+/// Ground speed in km/h between each pair of consecutive fixes, allowing for the trace crossing
+/// midnight.
+///
+/// Elapsed time is counted with a `MonotonicTime`. There is one speed for every pair of fixes,
+/// so `fixes.len() - 1` in total, except that a pair with no time between them, because they
+/// share a timestamp or the second steps back in time, is skipped rather than dividing by zero.
+///
+/// ```
+/// # use igc::{analysis::ground_speeds, records::BRecord};
+/// let fixes = [
+///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+///     BRecord::parse("B1201045152358N00032642WA0100501020").unwrap(),
+/// ];
+/// let speeds = ground_speeds(&fixes).collect::<Vec<_>>();
+/// assert!((speeds[0] - 155.).abs() < 1.);
+/// ```
+pub fn ground_speeds<'f>(fixes: &'f [BRecord]) -> impl Iterator<Item = f64> + 'f {
+    timed_pairs(fixes).filter_map(|(a, b, dt)| {
+        if dt == 0 {
+            return None;
+        }
+        Some(a.pos.haversine_distance_m(&b.pos) / f64::from(dt) * 3.6)
+    })
+}
+
+/// Vertical speed in m/s between each pair of consecutive fixes, positive when climbing, allowing
+/// for the trace crossing midnight.
+///
+/// As with `ground_speeds`, there is one speed for every pair of fixes except for those with no
+/// time between them, which are skipped.
+///
+/// ```
+/// # use igc::{analysis::vertical_speeds, records::{AltitudeSource, BRecord}};
+/// let fixes = [
+///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+///     BRecord::parse("B1201045152358N00032642WA0100501020").unwrap(),
+/// ];
+/// let climb = vertical_speeds(&fixes, AltitudeSource::Gps).collect::<Vec<_>>();
+/// assert_eq!(climb, vec![5.]);
+/// ```
+pub fn vertical_speeds<'f>(
+    fixes: &'f [BRecord],
+    source: AltitudeSource,
+) -> impl Iterator<Item = f64> + 'f {
+    timed_pairs(fixes).filter_map(move |(a, b, dt)| {
+        if dt == 0 {
+            return None;
+        }
+        let climb = i32::from(b.altitude(source)) - i32::from(a.altitude(source));
+        Some(f64::from(climb) / f64::from(dt))
+    })
+}
+
+/// The number of fixes before time `t`, also counting those at `t` if `inclusive`, found by a
+/// binary search over fixes sorted by timestamp.
+fn fixes_before(fixes: &[BRecord], t: Time, inclusive: bool) -> usize {
+    let (mut low, mut high) = (0, fixes.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let before = if inclusive {
+            fixes[mid].timestamp <= t
+        } else {
+            fixes[mid].timestamp < t
+        };
+        if before {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Find the fix at time `t`, or failing that the last fix before it.
+///
+/// `fixes` must be sorted by timestamp within a single UTC day, as the search is a binary search
+/// over the times of day. Returns `None` if every fix is after `t`. Of several fixes sharing a
+/// timestamp, the last is returned.
+///
+/// ```
+/// # use igc::{analysis::fix_at, records::BRecord, util::Time};
+/// let fixes = [
+///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+///     BRecord::parse("B1201045152358N00032642WA0100501020").unwrap(),
+/// ];
+/// assert_eq!(fix_at(&fixes, Time::from_hms(12, 1, 3)), Some(&fixes[0]));
+/// ```
+pub fn fix_at<'f, 'a>(fixes: &'f [BRecord<'a>], t: Time) -> Option<&'f BRecord<'a>> {
+    match fixes_before(fixes, t, true) {
+        0 => None,
+        index => Some(&fixes[index - 1]),
+    }
+}
+
+/// The fixes with timestamps between `start` and `end` inclusive.
+///
+/// As with `fix_at`, `fixes` must be sorted by timestamp within a single UTC day. The result is
+/// empty if no fixes fall in the range, including when `end` is before `start`.
+pub fn fix_range<'f, 'a>(
+    fixes: &'f [BRecord<'a>],
+    start: Time,
+    end: Time,
+) -> &'f [BRecord<'a>] {
+    let first = fixes_before(fixes, start, false);
+    let last = fixes_before(fixes, end, true);
+    if first < last {
+        &fixes[first..last]
+    } else {
+        &[]
+    }
+}
+
+/// Merge the fixes from several recordings of the same flight into a single track.
+///
+/// The fixes from all sources are interleaved by timestamp. Fixes from different sources whose
+/// timestamps are within `tolerance_secs` of each other are considered duplicates, and only one
+/// of them is kept: a valid fix is preferred over one with a navigation warning, and between two
+/// valid fixes the one with the lower FXA accuracy estimate, read using the extensions defined
+/// in `defs`. Otherwise the earlier fix wins, with ties broken by the order of `sources`. Fixes
+/// from the same source are never merged with each other.
+///
+/// Each source is expected to be sorted by timestamp, and all the fixes are assumed to fall
+/// within the same UTC day.
+pub fn merge_fixes(
+    sources: &[&[BRecord]],
+    defs: &[Extension],
+    tolerance_secs: u32,
+) -> Vec<BRecordBuf> {
+    let mut all_fixes = sources
+        .iter()
+        .enumerate()
+        .flat_map(|(source_idx, fixes)| fixes.iter().map(move |fix| (source_idx, fix)))
+        .collect::<Vec<_>>();
+
+    // A stable sort, so fixes with equal timestamps remain ordered by source
+    all_fixes.sort_by_key(|(_, fix)| fix.timestamp);
+
+    let mut merged: Vec<&BRecord> = Vec::with_capacity(all_fixes.len());
+    let mut merged_sources = Vec::with_capacity(all_fixes.len());
+    for (source_idx, fix) in all_fixes {
+        let duplicate = match (merged.last(), merged_sources.last()) {
+            (Some(last), Some(&last_source)) => {
+                last_source != source_idx && seconds_between(last, fix) <= tolerance_secs
+            }
+            _ => false,
+        };
+
+        if !duplicate {
+            merged.push(fix);
+            merged_sources.push(source_idx);
+        } else if fix.fix_valid.is_valid() {
+            let last = merged.last_mut().unwrap();
+            let more_accurate = match (fix.fix_accuracy(defs), last.fix_accuracy(defs)) {
+                (Some(accuracy), Some(last_accuracy)) => accuracy < last_accuracy,
+                _ => false,
+            };
+            if !last.fix_valid.is_valid() || more_accurate {
+                *last = fix;
+                *merged_sources.last_mut().unwrap() = source_idx;
+            }
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|fix| fix.clone().into_owned())
+        .collect()
+}
+
+/// Simplify a trace with the Ramer-Douglas-Peucker algorithm, returning the indices of the
+/// fixes to keep in ascending order.
+///
+/// A fix is dropped if it lies within `epsilon_m` meters of the straight line between the fixes
+/// kept either side of it, so the simplified trace never strays further than that from the
+/// original. The first and last fixes are always kept. Distances are measured with the cheap
+/// equirectangular approximation of `RawPosition::project_local`, centered on the first fix,
+/// which is accurate enough over the area covered by a single flight.
+///
+/// ```
+/// # use igc::{analysis::simplify, records::BRecord};
+/// let fixes = [
+///     BRecord::parse("B1201005152000N00032000WA0100001000").unwrap(),
+///     BRecord::parse("B1201045153000N00032000WA0100501020").unwrap(),
+///     BRecord::parse("B1201085154000N00032000WA0095000980").unwrap(),
+/// ];
+/// assert_eq!(simplify(&fixes, 10.), vec![0, 2]);
+/// ```
+pub fn simplify(fixes: &[BRecord], epsilon_m: f64) -> Vec<usize> {
+    if fixes.len() <= 2 {
+        return (0..fixes.len()).collect();
+    }
+
+    let origin = &fixes[0].pos;
+    let points = fixes
+        .iter()
+        .map(|fix| fix.pos.project_local(origin))
+        .collect::<Vec<_>>();
+
+    let last = fixes.len() - 1;
+    let mut keep = vec![false; fixes.len()];
+    keep[0] = true;
+    keep[last] = true;
+
+    // Spans between two kept fixes which still need checking, avoiding recursion so that long
+    // traces can't overflow the stack.
+    let mut spans = vec![(0, last)];
+    while let Some((start, end)) = spans.pop() {
+        let (a, b) = (points[start], points[end]);
+        let farthest = (start + 1..end)
+            .map(|i| (i, distance_to_segment(points[i], a, b)))
+            .fold(
+                None,
+                |farthest: Option<(usize, f64)>, (i, distance)| match farthest {
+                    Some((_, max)) if max >= distance => farthest,
+                    _ => Some((i, distance)),
+                },
+            );
+
+        if let Some((i, distance)) = farthest {
+            if distance > epsilon_m {
+                keep[i] = true;
+                spans.push((start, i));
+                spans.push((i, end));
+            }
+        }
+    }
+
+    keep.iter()
+        .enumerate()
+        .filter_map(|(i, &keep)| if keep { Some(i) } else { None })
+        .collect()
+}
+
+/// The distance from point `p` to the line segment from `a` to `b`, all in the same planar
+/// coordinates.
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0. {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq)
+            .max(0.)
+            .min(1.)
+    } else {
+        0.
+    };
+
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
+
+/// A sensible default ground speed in km/h above which a glider is considered to be flying, for
+/// use with `FlightStats::detect_takeoff_landing`.
+pub const DEFAULT_TAKEOFF_SPEED_KMH: f64 = 10.;
+
+/// The number of seconds a glider has to keep moving for before it is considered to be flying,
+/// so that being pushed around on the ground isn't mistaken for a flight.
+const MIN_FLYING_SECS: u32 = 60;
+
+/// The extent of a trace in decimal degrees, positive being north and east.
+///
+/// This is a simple minimum and maximum of each coordinate, so a trace crossing the
+/// antimeridian gets a box spanning almost the whole globe from west to east, rather than the
+/// narrow box around the 180th meridian it actually covers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+}
+
+/// Basic statistics about a flight, computed from its valid fixes.
+///
+/// Fixes with a navigation warning are ignored throughout. If there are no valid fixes, every
+/// field is `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlightStats {
+    /// Time of the first valid fix.
+    pub takeoff_time: Option<Time>,
+    /// Time of the last valid fix.
+    pub landing_time: Option<Time>,
+    /// Seconds between takeoff and landing, allowing for the flight crossing midnight.
+    pub duration_secs: Option<u32>,
+    pub max_gps_alt: Option<i16>,
+    pub min_gps_alt: Option<i16>,
+    pub max_pressure_alt: Option<i16>,
+    /// Great circle length in meters of the path through every valid fix.
+    pub distance_m: Option<f64>,
+}
+
+impl FlightStats {
+    /// ```
+    /// # use igc::{analysis::FlightStats, records::BRecord};
+    /// let fixes = [
+    ///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+    ///     BRecord::parse("B1201045152358N00032642WA0100501020").unwrap(),
+    ///     BRecord::parse("B1201085152450N00032642WA0095000980").unwrap(),
+    /// ];
+    /// let stats = FlightStats::from_fixes(&fixes);
+    /// assert_eq!(stats.duration_secs, Some(8));
+    /// assert_eq!(stats.max_gps_alt, Some(1020));
+    /// assert!((stats.distance_m.unwrap() - 343.).abs() < 1.);
+    /// ```
+    pub fn from_fixes(fixes: &[BRecord]) -> Self {
+        let mut valid = fixes.iter().filter(|fix| fix.fix_valid.is_valid());
+        let first = match valid.next() {
+            Some(fix) => fix,
+            None => {
+                return FlightStats {
+                    takeoff_time: None,
+                    landing_time: None,
+                    duration_secs: None,
+                    max_gps_alt: None,
+                    min_gps_alt: None,
+                    max_pressure_alt: None,
+                    distance_m: None,
+                }
+            }
+        };
+
+        let mut last = first;
+        let mut max_gps_alt = first.gps_alt;
+        let mut min_gps_alt = first.gps_alt;
+        let mut max_pressure_alt = first.pressure_alt;
+        let mut distance_m = 0.;
+        for fix in valid {
+            max_gps_alt = max_gps_alt.max(fix.gps_alt);
+            min_gps_alt = min_gps_alt.min(fix.gps_alt);
+            max_pressure_alt = max_pressure_alt.max(fix.pressure_alt);
+            distance_m += last.pos.haversine_distance_m(&fix.pos);
+            last = fix;
+        }
+
+        FlightStats {
+            takeoff_time: Some(first.timestamp),
+            landing_time: Some(last.timestamp),
+            duration_secs: Some(seconds_between(first, last)),
+            max_gps_alt: Some(max_gps_alt),
+            min_gps_alt: Some(min_gps_alt),
+            max_pressure_alt: Some(max_pressure_alt),
+            distance_m: Some(distance_m),
+        }
+    }
+
+    /// The median number of seconds between consecutive valid fixes, allowing for the flight
+    /// crossing midnight.
+    ///
+    /// The median is used rather than the mean so that the odd gap in recording, eg. from a loss
+    /// of GPS signal, doesn't skew the result. Returns `None` if there are fewer than two valid
+    /// fixes.
+    ///
+    /// ```
+    /// # use igc::{analysis::FlightStats, records::BRecord};
+    /// let fixes = [
+    ///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+    ///     BRecord::parse("B1201045152358N00032642WA0100501020").unwrap(),
+    ///     BRecord::parse("B1201085152450N00032642WA0095000980").unwrap(),
+    /// ];
+    /// assert_eq!(FlightStats::fix_interval_secs(&fixes), Some(4.));
+    /// ```
+    pub fn fix_interval_secs(fixes: &[BRecord]) -> Option<f64> {
+        let valid = fixes
+            .iter()
+            .filter(|fix| fix.fix_valid.is_valid())
+            .collect::<Vec<_>>();
+
+        let mut deltas = valid
+            .windows(2)
+            .map(|pair| seconds_between(pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        if deltas.is_empty() {
+            return None;
+        }
+
+        deltas.sort();
+        let mid = deltas.len() / 2;
+        Some(if deltas.len() % 2 == 0 {
+            (f64::from(deltas[mid - 1]) + f64::from(deltas[mid])) / 2.
+        } else {
+            f64::from(deltas[mid])
+        })
+    }
+
+    /// Find the takeoff and landing times of a flight from how fast the glider is moving, rather
+    /// than from when the logger was switched on and off.
+    ///
+    /// The glider is flying while the ground speed between consecutive valid fixes exceeds
+    /// `min_speed_kmh` (see `DEFAULT_TAKEOFF_SPEED_KMH`). Takeoff is the first fix of the first
+    /// spell of flying lasting at least a minute, and landing is the last fix of the last such
+    /// spell. Returns `None` if the glider never flew for a whole minute.
+    pub fn detect_takeoff_landing(
+        fixes: &[BRecord],
+        min_speed_kmh: f64,
+    ) -> Option<(Time, Time)> {
+        let valid = fixes
+            .iter()
+            .filter(|fix| fix.fix_valid.is_valid())
+            .collect::<Vec<_>>();
+        let is_flying = |a: &BRecord, b: &BRecord| {
+            let dt = seconds_between(a, b).max(1);
+            a.pos.haversine_distance_m(&b.pos) / f64::from(dt) * 3.6 > min_speed_kmh
+        };
+
+        // Each spell of flying as the indices of its first and last fix
+        let mut spells = Vec::new();
+        let mut spell_start = None;
+        for (i, pair) in valid.windows(2).enumerate() {
+            match (is_flying(pair[0], pair[1]), spell_start) {
+                (true, None) => spell_start = Some(i),
+                (false, Some(start)) => {
+                    spells.push((start, i));
+                    spell_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = spell_start {
+            spells.push((start, valid.len() - 1));
+        }
+
+        let mut flights = spells.into_iter().filter(|&(start, end)| {
+            seconds_between(valid[start], valid[end]) >= MIN_FLYING_SECS
+        });
+        let (takeoff, first_end) = flights.next()?;
+        let landing = flights.next_back().map_or(first_end, |(_, end)| end);
+
+        Some((valid[takeoff].timestamp, valid[landing].timestamp))
+    }
+
+    /// The smallest box containing every valid fix, or `None` if there are no valid fixes.
+    ///
+    /// See `BoundingBox` for how a trace crossing the antimeridian is handled.
+    ///
+    /// ```
+    /// # use igc::{analysis::FlightStats, records::BRecord};
+    /// let fixes = [
+    ///     BRecord::parse("B1201005130000N00030000WA0100001000").unwrap(),
+    ///     BRecord::parse("B1201045145000N00015000WA0100501020").unwrap(),
+    /// ];
+    /// let bbox = FlightStats::bounding_box(&fixes).unwrap();
+    /// assert_eq!((bbox.min_lat, bbox.max_lat), (51.5, 51.75));
+    /// assert_eq!((bbox.min_lon, bbox.max_lon), (-0.5, -0.25));
+    /// ```
+    pub fn bounding_box(fixes: &[BRecord]) -> Option<BoundingBox> {
+        let mut valid = fixes
+            .iter()
+            .filter(|fix| fix.fix_valid.is_valid())
+            .map(|fix| (f64::from(fix.pos.lat), f64::from(fix.pos.lon)));
+
+        let (lat, lon) = valid.next()?;
+        let mut bbox = BoundingBox {
+            min_lat: lat,
+            max_lat: lat,
+            min_lon: lon,
+            max_lon: lon,
+        };
+        for (lat, lon) in valid {
+            bbox.min_lat = bbox.min_lat.min(lat);
+            bbox.max_lat = bbox.max_lat.max(lat);
+            bbox.min_lon = bbox.min_lon.min(lon);
+            bbox.max_lon = bbox.max_lon.max(lon);
+        }
+
+        Some(bbox)
+    }
+
+    /// Total altitude gained in meters over the valid fixes, summing every climb between
+    /// consecutive fixes and ignoring every descent.
+    ///
+    /// ```
+    /// # use igc::{analysis::FlightStats, records::{AltitudeSource, BRecord}};
+    /// let fixes = [
+    ///     BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+    ///     BRecord::parse("B1201045152358N00032642WA0105001020").unwrap(),
+    ///     BRecord::parse("B1201085152450N00032642WA0095000980").unwrap(),
+    /// ];
+    /// assert_eq!(FlightStats::total_altitude_gain_m(&fixes, AltitudeSource::Pressure), 50);
+    /// assert_eq!(FlightStats::total_altitude_gain_m(&fixes, AltitudeSource::Gps), 20);
+    /// ```
+    pub fn total_altitude_gain_m(fixes: &[BRecord], source: AltitudeSource) -> i32 {
+        Self::smoothed_altitude_gain_m(fixes, source, 1)
+    }
+
+    /// As `total_altitude_gain_m`, but working on a moving average of the altitude over the last
+    /// `window` valid fixes, so that jitter in the altitude isn't counted as climb.
+    ///
+    /// A `window` of 0 or 1 does no smoothing.
+    pub fn smoothed_altitude_gain_m(
+        fixes: &[BRecord],
+        source: AltitudeSource,
+        window: usize,
+    ) -> i32 {
+        let altitudes = fixes
+            .iter()
+            .filter(|fix| fix.fix_valid.is_valid())
+            .map(|fix| f64::from(fix.altitude(source)))
+            .collect::<Vec<_>>();
+
+        let window = window.max(1);
+        let mut gain = 0.;
+        let mut last = None;
+        for alts in altitudes.windows(window) {
+            let alt = alts.iter().sum::<f64>() / alts.len() as f64;
+            if let Some(last) = last {
+                if alt > last {
+                    gain += alt - last;
+                }
+            }
+            last = Some(alt);
+        }
+
+        gain.round() as i32
+    }
+}
+
+/// A stretch of a flight spent circling, as found by `detect_thermals`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermalSegment {
+    /// Index of the fix at which circling began.
+    pub start_idx: usize,
+    /// Index of the fix at which circling ended, inclusive.
+    pub end_idx: usize,
+    /// Average climb rate in m/s between the first and last fix, negative if the glider sank.
+    pub avg_climb_ms: f64,
+    /// Number of full circles flown, as the total heading change divided by 360 degrees.
+    pub turns: f64,
+}
+
+/// Thresholds used by `detect_thermals`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThermalOptions {
+    /// The slowest rate of turn in degrees per second which counts as circling. A glider
+    /// thermalling typically turns at 10 to 20 degrees per second.
+    pub min_turn_rate_deg_s: f64,
+    /// The least number of full circles a segment must contain.
+    pub min_turns: f64,
+    /// The lowest average climb rate in m/s a segment must have.
+    pub min_climb_ms: f64,
+    /// Which altitude to measure the climb rate with.
+    pub alt_source: AltitudeSource,
+}
+
+impl Default for ThermalOptions {
+    fn default() -> Self {
+        ThermalOptions {
+            min_turn_rate_deg_s: 4.,
+            min_turns: 1.,
+            min_climb_ms: 0.,
+            alt_source: AltitudeSource::Gps,
+        }
+    }
+}
+
+/// Find where a glider circled in a climb.
+///
+/// The heading of the glider between each pair of consecutive fixes is taken as the bearing
+/// from one to the other, skipping pairs at the same position. The glider is circling while its
+/// heading keeps changing in the same direction at least as fast as
+/// `options.min_turn_rate_deg_s`. Each such spell is reported if the glider turned through at
+/// least `options.min_turns` full circles while climbing at `options.min_climb_ms` or better
+/// on average.
+///
+/// Fixes are expected to be in time order, and the returned indices are into `fixes`.
+pub fn detect_thermals(
+    fixes: &[BRecord],
+    options: &ThermalOptions,
+) -> Vec<ThermalSegment> {
+    // The heading of each leg which moved, along with the index of the fix it started from
+    let legs = fixes
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0].pos != pair[1].pos)
+        .map(|(i, pair)| (i, pair[0].pos.initial_bearing_deg(&pair[1].pos)))
+        .collect::<Vec<_>>();
+
+    let to_segment = |(start_idx, end_idx, turned): (usize, usize, f64)| {
+        let (start, end) = (&fixes[start_idx], &fixes[end_idx]);
+        let dt = seconds_between(start, end).max(1);
+        let climb = i32::from(end.altitude(options.alt_source))
+            - i32::from(start.altitude(options.alt_source));
+        ThermalSegment {
+            start_idx,
+            end_idx,
+            avg_climb_ms: f64::from(climb) / f64::from(dt),
+            turns: turned.abs() / 360.,
+        }
+    };
+    let is_thermal = |segment: &ThermalSegment| {
+        segment.turns >= options.min_turns && segment.avg_climb_ms >= options.min_climb_ms
+    };
+
+    let mut thermals = Vec::new();
+    // The current spell of circling as its first fix, last fix and signed heading change
+    let mut spell: Option<(usize, usize, f64)> = None;
+    for pair in legs.windows(2) {
+        let ((from_idx, from), (to_idx, to)) = (pair[0], pair[1]);
+        let mut turn = to - from;
+        if turn > 180. {
+            turn -= 360.;
+        } else if turn <= -180. {
+            turn += 360.;
+        }
+
+        let dt = seconds_between(&fixes[from_idx + 1], &fixes[to_idx + 1]).max(1);
+        let circling = turn.abs() / f64::from(dt) >= options.min_turn_rate_deg_s;
+        match spell {
+            Some((start, _, turned)) if circling && turned.signum() == turn.signum() => {
+                spell = Some((start, to_idx + 1, turned + turn));
+            }
+            _ => {
+                thermals.extend(spell.take().map(to_segment).filter(&is_thermal));
+                if circling {
+                    spell = Some((from_idx, to_idx + 1, turn));
+                }
+            }
+        }
+    }
+    thermals.extend(spell.map(to_segment).filter(&is_thermal));
+
+    thermals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::parse_fixes;
+    use crate::records::IRecord;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn realistic_leg_is_not_teleport() {
+        // ~0.17 km north in 4 seconds, so ~43m/s
+        let a = BRecord::parse("B1201005152265N00032642WA0100001000").unwrap();
+        let b = BRecord::parse("B1201045152358N00032642WA0100501005").unwrap();
+
+        assert!(!is_teleport(&a, &b, 70.));
+        assert!(is_teleport(&a, &b, 30.));
+    }
+
+    #[test]
+    fn obvious_teleport() {
+        // One degree of latitude (~111km) in one second
+        let a = BRecord::parse("B1201005152265N00032642WA0100001000").unwrap();
+        let b = BRecord::parse("B1201015252265N00032642WA0100001000").unwrap();
+
+        assert!(is_teleport(&a, &b, 70.));
+    }
+
+    #[test]
+    fn zero_time_delta() {
+        let a = BRecord::parse("B1201005152265N00032642WA0100001000").unwrap();
+        let b = BRecord::parse("B1201005152266N00032642WA0100001000").unwrap();
+        let c = BRecord::parse("B1201005252265N00032642WA0100001000").unwrap();
+
+        assert!(!is_teleport(&a, &a, 70.));
+        assert!(!is_teleport(&a, &b, 70.));
+        assert!(is_teleport(&a, &c, 70.));
+    }
+
+    #[test]
+    fn across_midnight() {
+        let a = BRecord::parse("B2359595152265N00032642WA0100001000").unwrap();
+        let b = BRecord::parse("B0000035152358N00032642WA0100501005").unwrap();
+
+        assert!(!is_teleport(&a, &b, 70.));
+    }
+
+    #[test]
+    fn speeds_across_midnight_and_steps_back() {
+        let fixes = [
+            BRecord::parse("B2359595152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B0000035152265N00032642WA0100001020").unwrap(),
+            // A glitch a second back in time, which is skipped
+            BRecord::parse("B0000025152265N00032642WA0100001020").unwrap(),
+            BRecord::parse("B0000135152265N00032642WA0100001000").unwrap(),
+        ];
+
+        assert_eq!(
+            vertical_speeds(&fixes, AltitudeSource::Gps).collect::<Vec<_>>(),
+            vec![5., -2.]
+        );
+        assert_eq!(ground_speeds(&fixes).count(), 2);
+    }
+
+    #[test]
+    fn track_length_sums_legs() {
+        let fixes = [
+            BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1201045152358N00032642WA0100501005").unwrap(),
+            BRecord::parse("B1201085152265N00032642WA0100001000").unwrap(),
+        ];
+        let leg = fixes[0].pos.haversine_distance_m(&fixes[1].pos);
+
+        assert_relative_eq!(track_length(&fixes, None), 2. * leg);
+        assert_relative_eq!(track_length(&fixes, Some(70.)), 2. * leg);
+        assert_relative_eq!(track_length(&fixes[..1], None), 0.);
+        assert_relative_eq!(track_length(&[], None), 0.);
+    }
+
+    #[test]
+    fn track_length_skips_teleports() {
+        let fixes = [
+            BRecord::parse("B1201005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1201015252265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1201045152358N00032642WA0100501005").unwrap(),
+        ];
+        let leg = fixes[0].pos.haversine_distance_m(&fixes[2].pos);
+
+        assert_relative_eq!(track_length(&fixes, Some(70.)), leg);
+        assert!(track_length(&fixes, None) > 200_000.);
+    }
+
+    #[test]
+    fn flight_stats() {
+        let fixes = [
+            BRecord::parse("B2359585152265N00032642WA0010000110").unwrap(),
+            BRecord::parse("B2359595152265N00032642WV0500005000").unwrap(),
+            BRecord::parse("B0000025152358N00032642WA0012000090").unwrap(),
+            BRecord::parse("B0000065152265N00032642WA0008000100").unwrap(),
+        ];
+        let leg = fixes[0].pos.haversine_distance_m(&fixes[2].pos);
+
+        let stats = FlightStats::from_fixes(&fixes);
+        assert_eq!(stats.takeoff_time, Some(Time::from_hms(23, 59, 58)));
+        assert_eq!(stats.landing_time, Some(Time::from_hms(0, 0, 6)));
+        assert_eq!(stats.duration_secs, Some(8));
+        assert_eq!(stats.max_gps_alt, Some(110));
+        assert_eq!(stats.min_gps_alt, Some(90));
+        assert_eq!(stats.max_pressure_alt, Some(120));
+        assert_relative_eq!(stats.distance_m.unwrap(), 2. * leg);
+    }
+
+    #[test]
+    fn flight_stats_single_fix() {
+        let fixes = [BRecord::parse("B1201005152265N00032642WA0100001000").unwrap()];
+        let stats = FlightStats::from_fixes(&fixes);
+        assert_eq!(stats.duration_secs, Some(0));
+        assert_eq!(stats.distance_m, Some(0.));
+    }
+
+    #[test]
+    fn flight_stats_without_valid_fixes() {
+        let none = FlightStats {
+            takeoff_time: None,
+            landing_time: None,
+            duration_secs: None,
+            max_gps_alt: None,
+            min_gps_alt: None,
+            max_pressure_alt: None,
+            distance_m: None,
+        };
+        let fixes = [BRecord::parse("B1201005152265N00032642WV0100001000").unwrap()];
+
+        assert_eq!(FlightStats::from_fixes(&[]), none);
+        assert_eq!(FlightStats::from_fixes(&fixes), none);
+    }
+
+    #[test]
+    fn fix_interval_evenly_spaced() {
+        let fixes = [
+            BRecord::parse("B2359585152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B2359595152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B0000005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B0000015152265N00032642WA0100001000").unwrap(),
+        ];
+        assert_eq!(FlightStats::fix_interval_secs(&fixes), Some(1.));
+    }
+
+    #[test]
+    fn fix_interval_with_gap() {
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200085152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1210085152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1210125152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1210165152265N00032642WA0100001000").unwrap(),
+        ];
+        assert_eq!(FlightStats::fix_interval_secs(&fixes), Some(4.));
+    }
+
+    #[test]
+    fn fix_interval_too_few_fixes() {
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152265N00032642WV0100001000").unwrap(),
+        ];
+        assert_eq!(FlightStats::fix_interval_secs(&[]), None);
+        assert_eq!(FlightStats::fix_interval_secs(&fixes), None);
+    }
+
+    #[test]
+    fn bounding_box_extremes() {
+        let fixes = [
+            "B1201005130000N00030000WA0100001000",
+            "B1201045145000N00015000WA0100501020",
+            "B1201085115000S00115000EA0100501020",
+            "B1201125200000N00045000WV0100501020",
+            "B1201165140000N00010000EA0100501020",
+        ]
+        .iter()
+        .map(|line| BRecord::parse(line).unwrap())
+        .collect::<Vec<_>>();
+
+        let bbox = FlightStats::bounding_box(&fixes).unwrap();
+        assert_relative_eq!(bbox.min_lat, -51.25);
+        assert_relative_eq!(bbox.max_lat, 51.75);
+        assert_relative_eq!(bbox.min_lon, -0.5);
+        assert_relative_eq!(bbox.max_lon, 1.25);
+
+        assert_eq!(FlightStats::bounding_box(&[]), None);
+        assert_eq!(FlightStats::bounding_box(&fixes[3..4]), None);
+    }
+
+    #[test]
+    fn altitude_gain() {
+        // Pressure altitude goes 100 -> 300 -> 150 -> 250, GPS altitude 100 -> 90 -> 130
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0010000100").unwrap(),
+            BRecord::parse("B1200045152265N00032642WA0030000090").unwrap(),
+            BRecord::parse("B1200065152265N00032642WV0900009000").unwrap(),
+            BRecord::parse("B1200085152265N00032642WA0015000090").unwrap(),
+            BRecord::parse("B1200125152265N00032642WA0025000130").unwrap(),
+        ];
+
+        assert_eq!(
+            FlightStats::total_altitude_gain_m(&fixes, AltitudeSource::Pressure),
+            200 + 100
+        );
+        assert_eq!(
+            FlightStats::total_altitude_gain_m(&fixes, AltitudeSource::Gps),
+            40
+        );
+        assert_eq!(
+            FlightStats::total_altitude_gain_m(&[], AltitudeSource::Gps),
+            0
+        );
+    }
+
+    #[test]
+    fn smoothed_altitude_gain_ignores_jitter() {
+        // GPS altitude jitters between 100 and 104 while otherwise level
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0010000100").unwrap(),
+            BRecord::parse("B1200015152265N00032642WA0010000104").unwrap(),
+            BRecord::parse("B1200025152265N00032642WA0010000100").unwrap(),
+            BRecord::parse("B1200035152265N00032642WA0010000104").unwrap(),
+            BRecord::parse("B1200045152265N00032642WA0010000100").unwrap(),
+            BRecord::parse("B1200055152265N00032642WA0010000104").unwrap(),
+        ];
+
+        assert_eq!(
+            FlightStats::total_altitude_gain_m(&fixes, AltitudeSource::Gps),
+            12
+        );
+        assert_eq!(
+            FlightStats::smoothed_altitude_gain_m(&fixes, AltitudeSource::Gps, 2),
+            0
+        );
+        assert_eq!(
+            FlightStats::smoothed_altitude_gain_m(&fixes, AltitudeSource::Gps, 0),
+            12
+        );
+    }
+
+    /// The line for a fix `secs` seconds after 12:00:00, `north_thousandths` thousandths of a
+    /// minute north of a fixed point.
+    fn fix_line(secs: u32, north_thousandths: u32) -> String {
+        format!(
+            "B12{:02}{:02}51{:05}N00032642WA0100001000",
+            secs / 60,
+            secs % 60,
+            22_650 + north_thousandths
+        )
+    }
+
+    #[test]
+    fn simplify_collapses_colinear_fixes() {
+        // Due north for five fixes, then due east for five more
+        let mut lines = (0..5)
+            .map(|i| {
+                format!(
+                    "B1200{:02}51{:05}N00030000WA0100001000",
+                    i,
+                    30_000 + i * 100
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.extend((1..6).map(|i| {
+            format!(
+                "B1201{:02}5130400N000{:05}WA0100001000",
+                i,
+                30_000 - i * 100
+            )
+        }));
+        let text = lines.join("\n");
+        let fixes = parse_fixes(&text);
+
+        assert_eq!(simplify(&fixes, 1.), vec![0, 4, 9]);
+        assert_eq!(simplify(&fixes, 1_000.), vec![0, 9]);
+    }
+
+    #[test]
+    fn simplify_zigzag() {
+        // Zigzagging 115m to the west of a line due north and back
+        let lines = (0..9)
+            .map(|i| {
+                let lon = if i % 2 == 0 { 30_000 } else { 30_100 };
+                format!(
+                    "B1200{:02}51{:05}N000{:05}WA0100001000",
+                    i,
+                    30_000 + i * 100,
+                    lon
+                )
+            })
+            .collect::<Vec<_>>();
+        let text = lines.join("\n");
+        let fixes = parse_fixes(&text);
+
+        assert_eq!(simplify(&fixes, 50.), (0..9).collect::<Vec<_>>());
+        assert_eq!(simplify(&fixes, 200.), vec![0, 8]);
+        assert_eq!(simplify(&fixes[..2], 200.), vec![0, 1]);
+        assert!(simplify(&[], 200.).is_empty());
+    }
+
+    #[test]
+    fn detect_thermals_in_circling_climb() {
+        use crate::util::RawPosition;
+
+        const METERS_PER_DEGREE: f64 = 111_195.;
+        let line = |secs: u32, lat: f64, lon: f64, alt: i32| {
+            format!(
+                "B{}{}A{:05}{:05}",
+                Time::from_hms(12, (secs / 60) as u8, (secs % 60) as u8),
+                RawPosition::from_degrees(lat, lon).unwrap(),
+                alt,
+                alt
+            )
+        };
+
+        let mut lines = Vec::new();
+        // Gliding north for ten seconds
+        for i in 0..10u32 {
+            let lat = 51.5 - f64::from(10 - i) * 0.0005;
+            lines.push(line(i, lat, -0.5, 1_000 - i as i32));
+        }
+        // Three circles of 100m radius clockwise, twenty seconds each, climbing at 2 m/s
+        let radius = 100. / METERS_PER_DEGREE;
+        let lon_scale = 51.5f64.to_radians().cos();
+        for k in 0..60u32 {
+            let angle = f64::from(k * 18).to_radians();
+            let lat = 51.5 + radius - radius * angle.cos();
+            let lon = -0.5 - radius * angle.sin() / lon_scale;
+            lines.push(line(10 + k, lat, lon, 990 + 2 * k as i32));
+        }
+        // Gliding north again from where the last circle finished
+        let angle = f64::from(59 * 18).to_radians();
+        let lat = 51.5 + radius - radius * angle.cos();
+        let lon = -0.5 - radius * angle.sin() / lon_scale;
+        for i in 0..20u32 {
+            let lat = lat + f64::from(i) * 0.0005;
+            lines.push(line(70 + i, lat, lon, 1_110 - i as i32));
+        }
+        let text = lines.join("\n");
+        let fixes = parse_fixes(&text);
+
+        let thermals = detect_thermals(&fixes, &ThermalOptions::default());
+        assert_eq!(thermals.len(), 1, "{:?}", thermals);
+        let thermal = &thermals[0];
+        assert!((9..=11).contains(&thermal.start_idx), "{:?}", thermal);
+        assert!((68..=71).contains(&thermal.end_idx), "{:?}", thermal);
+        assert!(thermal.turns > 2.7 && thermal.turns < 3.4, "{:?}", thermal);
+        assert_relative_eq!(thermal.avg_climb_ms, 2., max_relative = 0.1);
+
+        // Demanding a stronger climb, or more circles, finds nothing
+        let options = ThermalOptions {
+            min_climb_ms: 3.,
+            ..ThermalOptions::default()
+        };
+        assert!(detect_thermals(&fixes, &options).is_empty());
+        let options = ThermalOptions {
+            min_turns: 4.,
+            ..ThermalOptions::default()
+        };
+        assert!(detect_thermals(&fixes, &options).is_empty());
+
+        // Straight flight has no thermals
+        assert!(detect_thermals(&fixes[..10], &ThermalOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn detect_takeoff_landing_ignores_ground_time() {
+        let mut lines = Vec::new();
+        // Sitting still for a minute, with a bit of GPS wander
+        for i in 0..6 {
+            lines.push(fix_line(i * 10, i % 2));
+        }
+        // Flying north at ~46m/s for two minutes
+        for i in 0..=30 {
+            lines.push(fix_line(60 + i * 4, 100 * i));
+        }
+        // Sitting still again
+        for i in 1..6 {
+            lines.push(fix_line(180 + i * 10, 3000));
+        }
+        let text = lines.join("\n");
+        let fixes = parse_fixes(&text);
+
+        assert_eq!(
+            FlightStats::detect_takeoff_landing(&fixes, DEFAULT_TAKEOFF_SPEED_KMH),
+            Some((Time::from_hms(12, 1, 0), Time::from_hms(12, 3, 0)))
+        );
+        assert_eq!(FlightStats::detect_takeoff_landing(&fixes, 500.), None);
+    }
+
+    #[test]
+    fn detect_takeoff_landing_needs_sustained_movement() {
+        // Moving for only 20 seconds
+        let lines = [
+            fix_line(0, 0),
+            fix_line(10, 0),
+            fix_line(20, 500),
+            fix_line(30, 1000),
+            fix_line(40, 1000),
+        ];
+        let text = lines.join("\n");
+        let fixes = parse_fixes(&text);
+
+        assert_eq!(
+            FlightStats::detect_takeoff_landing(&fixes, DEFAULT_TAKEOFF_SPEED_KMH),
+            None
+        );
+        assert_eq!(
+            FlightStats::detect_takeoff_landing(&[], DEFAULT_TAKEOFF_SPEED_KMH),
+            None
+        );
+    }
+
+    #[test]
+    fn speed_series() {
+        let fixes = [
+            BRecord::parse("B2359585152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B0000025152358N00032642WA0100801020").unwrap(),
+            BRecord::parse("B0000025152358N00032642WA0100801020").unwrap(),
+            BRecord::parse("B0000125152358N00032642WA0099801010").unwrap(),
+        ];
+        let leg = fixes[0].pos.haversine_distance_m(&fixes[1].pos);
+
+        let ground = ground_speeds(&fixes).collect::<Vec<_>>();
+        assert_eq!(ground.len(), 2);
+        assert_relative_eq!(ground[0], leg / 4. * 3.6);
+        assert_relative_eq!(ground[1], 0.);
+
+        let pressure =
+            vertical_speeds(&fixes, AltitudeSource::Pressure).collect::<Vec<_>>();
+        assert_eq!(pressure, vec![2., -1.]);
+        let gps = vertical_speeds(&fixes, AltitudeSource::Gps).collect::<Vec<_>>();
+        assert_eq!(gps, vec![5., -1.]);
+
+        assert_eq!(ground_speeds(&fixes[..1]).count(), 0);
+    }
+
+    #[test]
+    fn fix_lookup() {
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200085152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200125152265N00032642WA0100001000").unwrap(),
+        ];
+        let t = |secs| Time::from_hms(12, 0, secs);
+
+        // Exact hits
+        assert_eq!(fix_at(&fixes, t(0)), Some(&fixes[0]));
+        assert_eq!(fix_at(&fixes, t(8)), Some(&fixes[2]));
+        // Between fixes
+        assert_eq!(fix_at(&fixes, t(7)), Some(&fixes[1]));
+        // Out of range
+        assert_eq!(fix_at(&fixes, Time::from_hms(11, 59, 59)), None);
+        assert_eq!(fix_at(&fixes, Time::from_hms(13, 0, 0)), Some(&fixes[3]));
+        assert_eq!(fix_at(&[], t(0)), None);
+
+        assert_eq!(fix_range(&fixes, t(4), t(8)), &fixes[1..3]);
+        assert_eq!(fix_range(&fixes, t(3), t(9)), &fixes[1..3]);
+        assert_eq!(
+            fix_range(&fixes, Time::from_hms(11, 0, 0), t(59)),
+            &fixes[..]
+        );
+        assert!(fix_range(&fixes, t(5), t(7)).is_empty());
+        assert!(fix_range(&fixes, t(8), t(4)).is_empty());
+        assert!(fix_range(&fixes, t(13), t(59)).is_empty());
+    }
+
+    fn owned(fixes: &[BRecord]) -> Vec<BRecordBuf> {
+        fixes.iter().map(|fix| fix.clone().into_owned()).collect()
+    }
+
+    #[test]
+    fn merge_interleaves_and_dedups() {
+        let a = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152270N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200085152275N00032642WV0100001000").unwrap(),
+        ];
+        let b = [
+            BRecord::parse("B1200015152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152271N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200085152276N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200125152280N00032642WA0100001000").unwrap(),
+        ];
+
+        let merged = merge_fixes(&[&a, &b], &[], 0);
+        assert_eq!(
+            merged,
+            owned(&[
+                a[0].clone(),
+                b[0].clone(),
+                // Equal quality, so the first source wins
+                a[1].clone(),
+                // The first source has a nav warning here
+                b[2].clone(),
+                b[3].clone(),
+            ])
+        );
+    }
+
+    #[test]
+    fn merge_with_tolerance() {
+        let a = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200015152266N00032642WA0100001000").unwrap(),
+        ];
+        let b = [BRecord::parse("B1200015152265N00032642WA0100001000").unwrap()];
+
+        // Fixes from the same source are never merged, even within the tolerance
+        assert_eq!(merge_fixes(&[&a], &[], 5), owned(&a));
+        assert_eq!(merge_fixes(&[&a, &b], &[], 1), owned(&a));
+        assert_eq!(merge_fixes(&[&b, &a], &[], 1), owned(&a));
+    }
+
+    #[test]
+    fn merge_replacement_takes_source() {
+        let a = [BRecord::parse("B1200005152265N00032642WV0100001000").unwrap()];
+        let b = [
+            BRecord::parse("B1200005152265N00032642WA0100001000").unwrap(),
+            BRecord::parse("B1200045152266N00032642WA0100001000").unwrap(),
+        ];
+
+        // Once b's first fix replaces a's, b's next fix is from the same source as the last
+        // kept fix, so it must not be treated as a duplicate
+        assert_eq!(merge_fixes(&[&a, &b], &[], 5), owned(&b));
+    }
+
+    #[test]
+    fn merge_prefers_lower_fix_accuracy() {
+        let i_record = IRecord::parse("I013638FXA").unwrap();
+        let defs = &i_record.extensions;
+        let a = [BRecord::parse("B1200005152265N00032642WA0100001000025").unwrap()];
+        let b = [BRecord::parse("B1200005152266N00032642WA0100001000008").unwrap()];
+        let c = [BRecord::parse("B1200005152267N00032642WA0100001000").unwrap()];
+
+        assert_eq!(merge_fixes(&[&a, &b], defs, 0), owned(&b));
+        assert_eq!(merge_fixes(&[&b, &a], defs, 0), owned(&b));
+        // Without FXA to compare, the first source wins
+        assert_eq!(merge_fixes(&[&a, &b], &[], 0), owned(&a));
+        assert_eq!(merge_fixes(&[&c, &b], defs, 0), owned(&c));
+    }
+
+    #[test]
+    fn merge_no_sources() {
+        assert!(merge_fixes(&[], &[], 1).is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+use std::io::{self, Write};
+
+use crate::records::{BRecord, FixValid};
+use crate::util::{CenturyConfig, Date, MonotonicTime, SECONDS_PER_DAY};
+
+/// Options controlling how a trace is written out as GPX.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GpxOptions {
+    /// The UTC date of the first fix, usually taken from the file's HFDTE header.
+    ///
+    /// Fixes after the trace crosses midnight are given the following day(s).
+    pub date: Date,
+
+    /// How to expand the two digit year of `date`.
+    pub century: CenturyConfig,
+
+    /// Whether to include fixes with a navigation warning, which are skipped by default.
+    pub include_nav_warnings: bool,
+}
+
+impl GpxOptions {
+    pub fn new(date: Date) -> Self {
+        GpxOptions {
+            date,
+            century: CenturyConfig::default(),
+            include_nav_warnings: false,
+        }
+    }
+}
+
+/// Write a sequence of fixes as a GPX 1.1 document containing a single track.
+///
+/// Each fix becomes a `<trkpt>`, with its GPS altitude as the elevation.
+///
+/// ```
+/// # use igc::{export::{to_gpx, GpxOptions}, records::BRecord, util::Date};
+/// let fixes = [BRecord::parse("B0941145152265N00032642WA0011500115").unwrap()];
+/// let mut out = Vec::new();
+/// to_gpx(&fixes, &GpxOptions::new(Date::from_dmy(14, 7, 18)), &mut out).unwrap();
+/// assert!(String::from_utf8(out).unwrap().contains("<time>2018-07-14T09:41:14Z</time>"));
+/// ```
+pub fn to_gpx<W: Write>(
+    fixes: &[BRecord],
+    options: &GpxOptions,
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<gpx version="1.1" creator="igc-rs" xmlns="http://www.topografix.com/GPX/1/1">"#
+    )?;
+    writeln!(out, "  <trk>")?;
+    writeln!(out, "    <trkseg>")?;
+
+    let start = fixes
+        .first()
+        .map_or(0, |fix| fix.timestamp.seconds_since_midnight());
+    let mut monotonic = MonotonicTime::new();
+    for fix in fixes {
+        // Every fix is pushed, so that skipped fixes still count towards crossing midnight
+        let elapsed = monotonic.push(fix.timestamp);
+        if fix.fix_valid == FixValid::NavWarning && !options.include_nav_warnings {
+            continue;
+        }
+
+        writeln!(
+            out,
+            r#"      <trkpt lat="{:.6}" lon="{:.6}">"#,
+            f64::from(fix.pos.lat),
+            f64::from(fix.pos.lon)
+        )?;
+        writeln!(out, "        <ele>{}</ele>", fix.gps_alt)?;
+        writeln!(
+            out,
+            "        <time>{}</time>",
+            rfc3339(options.date, options.century, start + elapsed)
+        )?;
+        writeln!(out, "      </trkpt>")?;
+    }
+
+    writeln!(out, "    </trkseg>")?;
+    writeln!(out, "  </trk>")?;
+    writeln!(out, "</gpx>")
+}
+
+/// Format an instant, given as a number of seconds after the start of `date`, as an RFC 3339 UTC
+/// timestamp.
+fn rfc3339(date: Date, century: CenturyConfig, seconds: u32) -> String {
+    let days = days_from_civil(date.full_year(century), date.month, date.day)
+        + i64::from(seconds / SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let seconds = seconds % SECONDS_PER_DAY;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Number of days between 1970-01-01 and the given day of the proleptic Gregorian calendar.
+fn days_from_civil(year: u16, month: u8, day: u8) -> i64 {
+    let year = i64::from(year) - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`, returning a (year, month, day) triplet.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        for days in -1000..30_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year as u16, month, day), days);
+        }
+    }
+
+    #[test]
+    fn rfc3339_rolls_over_days() {
+        let century = CenturyConfig::default();
+        assert_eq!(
+            rfc3339(Date::from_dmy(14, 7, 18), century, 34_874),
+            "2018-07-14T09:41:14Z"
+        );
+        assert_eq!(
+            rfc3339(Date::from_dmy(31, 12, 99), century, SECONDS_PER_DAY + 3),
+            "2000-01-01T00:00:03Z"
+        );
+        assert_eq!(
+            rfc3339(Date::from_dmy(28, 2, 20), century, SECONDS_PER_DAY),
+            "2020-02-29T00:00:00Z"
+        );
+    }
+
+    fn write(fixes: &[BRecord], options: &GpxOptions) -> String {
+        let mut out = Vec::new();
+        to_gpx(fixes, options, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn round_trip_through_gpx_parser() {
+        let fixes = [
+            BRecord::parse("B2359585152265N00032642WA0011500115").unwrap(),
+            BRecord::parse("B2359595152270S00032642EV0011600116").unwrap(),
+            BRecord::parse("B0000015152275N00032642WA00117-0012").unwrap(),
+        ];
+        let gpx = write(&fixes, &GpxOptions::new(Date::from_dmy(31, 12, 18)));
+
+        let parsed = gpx_reader::read(gpx.as_bytes()).unwrap();
+        assert_eq!(parsed.tracks.len(), 1);
+        assert_eq!(parsed.tracks[0].segments.len(), 1);
+
+        let points = &parsed.tracks[0].segments[0].points;
+        assert_eq!(points.len(), 2);
+        assert!((points[0].point().y() - 51.871_083).abs() < 1e-6);
+        assert!((points[0].point().x() - -0.544_033).abs() < 1e-6);
+        assert_eq!(points[0].elevation, Some(115.));
+        assert_eq!(points[1].elevation, Some(-12.));
+        assert!(points.iter().all(|point| point.time.is_some()));
+
+        assert!(gpx.contains("<time>2018-12-31T23:59:58Z</time>"));
+        assert!(gpx.contains("<time>2019-01-01T00:00:01Z</time>"));
+    }
+
+    #[test]
+    fn include_nav_warnings() {
+        let fixes = [
+            BRecord::parse("B1200005152265N00032642WA0011500115").unwrap(),
+            BRecord::parse("B1200015152270S00032642EV0011600116").unwrap(),
+        ];
+        let mut options = GpxOptions::new(Date::from_dmy(1, 6, 20));
+        options.include_nav_warnings = true;
+        let gpx = write(&fixes, &options);
+
+        assert_eq!(gpx.matches("<trkpt ").count(), 2);
+        assert!(gpx.contains(r#"<trkpt lat="-51.871167" lon="0.544033">"#));
+    }
+
+    #[test]
+    fn empty_trace() {
+        let gpx = write(&[], &GpxOptions::new(Date::from_dmy(1, 6, 20)));
+        let parsed = gpx_reader::read(gpx.as_bytes()).unwrap();
+        assert!(parsed.tracks[0].segments[0].points.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::io::{self, Write};
+
+use crate::records::{AltitudeSource, BRecord};
+
+/// How a viewer should interpret the altitudes in a KML document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AltitudeMode {
+    /// Ignore the altitudes, drawing the track on the ground.
+    ClampToGround,
+
+    /// Altitudes are relative to sea level.
+    Absolute,
+}
+
+impl AltitudeMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            AltitudeMode::ClampToGround => "clampToGround",
+            AltitudeMode::Absolute => "absolute",
+        }
+    }
+}
+
+/// Options controlling how a trace is written out as KML.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KmlOptions {
+    pub altitude_source: AltitudeSource,
+    pub altitude_mode: AltitudeMode,
+
+    /// Whether to include fixes with a navigation warning, which are skipped by default.
+    pub include_nav_warnings: bool,
+}
+
+impl Default for KmlOptions {
+    fn default() -> Self {
+        KmlOptions {
+            altitude_source: AltitudeSource::Gps,
+            altitude_mode: AltitudeMode::Absolute,
+            include_nav_warnings: false,
+        }
+    }
+}
+
+/// Write a sequence of fixes as a KML document containing a single `<Placemark>`, with the
+/// trace as a `<LineString>` of `lon,lat,alt` tuples.
+///
+/// ```
+/// # use igc::{export::{to_kml, KmlOptions}, records::BRecord};
+/// let fixes = [BRecord::parse("B0941145152265N00032642WA0011500115").unwrap()];
+/// let mut out = Vec::new();
+/// to_kml(&fixes, KmlOptions::default(), &mut out).unwrap();
+/// assert!(String::from_utf8(out).unwrap().contains("-0.544033,51.871083,115"));
+/// ```
+pub fn to_kml<W: Write>(
+    fixes: &[BRecord],
+    opts: KmlOptions,
+    out: &mut W,
+) -> io::Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(out, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+    writeln!(out, "  <Placemark>")?;
+    writeln!(out, "    <LineString>")?;
+    writeln!(
+        out,
+        "      <altitudeMode>{}</altitudeMode>",
+        opts.altitude_mode.as_str()
+    )?;
+    writeln!(out, "      <coordinates>")?;
+
+    let fixes = fixes
+        .iter()
+        .filter(|fix| fix.fix_valid.is_valid() || opts.include_nav_warnings);
+    for fix in fixes {
+        let alt = fix.altitude(opts.altitude_source);
+        writeln!(
+            out,
+            "        {:.6},{:.6},{}",
+            f64::from(fix.pos.lon),
+            f64::from(fix.pos.lat),
+            alt
+        )?;
+    }
+
+    writeln!(out, "      </coordinates>")?;
+    writeln!(out, "    </LineString>")?;
+    writeln!(out, "  </Placemark>")?;
+    writeln!(out, "</kml>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXES: [&str; 3] = [
+        "B1200005152265N00032642WA0011500120",
+        "B1200015152270S00032642EV0011600121",
+        "B1200025152275N00032642WA0011700122",
+    ];
+
+    /// Returns the coordinate tuples written for the fixes.
+    fn coordinates(opts: KmlOptions) -> Vec<String> {
+        let fixes = FIXES
+            .iter()
+            .map(|line| BRecord::parse(line).unwrap())
+            .collect::<Vec<_>>();
+        let mut out = Vec::new();
+        to_kml(&fixes, opts, &mut out).unwrap();
+
+        let kml = String::from_utf8(out).unwrap();
+        let start = kml.find("<coordinates>").unwrap() + "<coordinates>".len();
+        let end = kml.find("</coordinates>").unwrap();
+        kml[start..end]
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn one_tuple_per_included_fix() {
+        assert_eq!(coordinates(KmlOptions::default()).len(), 2);
+
+        let coords = coordinates(KmlOptions {
+            include_nav_warnings: true,
+            ..KmlOptions::default()
+        });
+        assert_eq!(coords.len(), 3);
+        assert_eq!(coords[1], "0.544033,-51.871167,121");
+    }
+
+    #[test]
+    fn altitude_source() {
+        assert_eq!(
+            coordinates(KmlOptions::default()),
+            vec!["-0.544033,51.871083,120", "-0.544033,51.871250,122"]
+        );
+
+        let opts = KmlOptions {
+            altitude_source: AltitudeSource::Pressure,
+            ..KmlOptions::default()
+        };
+        assert_eq!(
+            coordinates(opts),
+            vec!["-0.544033,51.871083,115", "-0.544033,51.871250,117"]
+        );
+    }
+
+    #[test]
+    fn altitude_mode() {
+        let mut out = Vec::new();
+        let opts = KmlOptions {
+            altitude_mode: AltitudeMode::ClampToGround,
+            ..KmlOptions::default()
+        };
+        to_kml(&[], opts, &mut out).unwrap();
+
+        let kml = String::from_utf8(out).unwrap();
+        assert!(kml.contains("<altitudeMode>clampToGround</altitudeMode>"));
+    }
+}
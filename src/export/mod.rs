@@ -0,0 +1,13 @@
+//! Conversion of parsed flight traces into formats understood by other mapping tools
+//!
+//! Each format is gated behind a cargo feature of the same name.
+
+#[cfg(feature = "gpx")]
+mod gpx;
+#[cfg(feature = "kml")]
+mod kml;
+
+#[cfg(feature = "gpx")]
+pub use self::gpx::{to_gpx, GpxOptions};
+#[cfg(feature = "kml")]
+pub use self::kml::{to_kml, AltitudeMode, KmlOptions};
@@ -0,0 +1,159 @@
+//! Generation of synthetic IGC files, for testing code that consumes them
+
+use crate::records::Record;
+#[cfg(test)]
+use crate::records::RecordReader;
+#[cfg(feature = "std")]
+use crate::records::{ARecord, BRecord, DataSource, FixValid, HRecord};
+use crate::util::Date;
+#[cfg(feature = "std")]
+use crate::util::{Manufacturer, RawPosition, Time, SECONDS_PER_DAY};
+
+/// The unique ID given to the logger in a synthesized file.
+pub const SYNTHETIC_LOGGER_ID: &str = "SYN";
+
+/// The flight date of a synthesized file.
+pub const SYNTHETIC_DATE: Date = Date {
+    day: 1,
+    month: 6,
+    year: 20,
+};
+
+/// The bearing, in degrees clockwise from true north, along which the fixes of a synthesized
+/// file step.
+pub const SYNTHETIC_BEARING_DEG: f64 = 45.;
+
+/// The distance in meters between consecutive fixes of a synthesized file.
+pub const SYNTHETIC_STEP_M: f64 = 20.;
+
+/// The pressure and GPS altitude of every fix in a synthesized file.
+pub const SYNTHETIC_ALT: i16 = 1000;
+
+/// Synthesize a minimal valid IGC file, consisting of an A record, a date header and `fixes` B
+/// records.
+///
+/// The fixes are one second apart starting at 12:00:00 UTC, the first being at `start` and
+/// each subsequent one `SYNTHETIC_STEP_M` meters further along `SYNTHETIC_BEARING_DEG`. Lines are
+/// terminated with CRLF, as mandated by the specification.
+///
+/// Panics if `manufacturer` has no three character code.
+///
+/// ```
+/// # use igc::{fixtures::synthesize, records::{Record, RecordReader}, util::Manufacturer};
+/// let start = "5152265N00032642W".parse().unwrap();
+/// let file = synthesize(Manufacturer::LxNav, start, 10);
+/// let records = RecordReader::new(&file).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(records.len(), 12);
+/// ```
+#[cfg(feature = "std")]
+pub fn synthesize(
+    manufacturer: Manufacturer,
+    start: RawPosition,
+    fixes: usize,
+) -> String {
+    assert!(
+        manufacturer.to_triple_char().is_some(),
+        "Synthesized files need a three character manufacturer code"
+    );
+
+    let date = SYNTHETIC_DATE.to_string();
+    let mut records = vec![
+        Record::A(ARecord::new(manufacturer, SYNTHETIC_LOGGER_ID, None)),
+        Record::H(HRecord {
+            data_source: DataSource::FVU,
+            mnemonic: "DTE",
+            friendly_name: None,
+            data: &date,
+        }),
+    ];
+
+    let start_secs = Time::from_hms(12, 0, 0).seconds_since_midnight() as usize;
+    let mut pos = start;
+    for i in 0..fixes {
+        let secs = (start_secs + i) % SECONDS_PER_DAY as usize;
+        let timestamp = Time::from_hms(
+            (secs / 3600) as u8,
+            (secs / 60 % 60) as u8,
+            (secs % 60) as u8,
+        );
+
+        let next_pos = pos.offset(SYNTHETIC_BEARING_DEG, SYNTHETIC_STEP_M);
+        records.push(Record::B(BRecord::new(
+            timestamp,
+            pos,
+            FixValid::Valid,
+            SYNTHETIC_ALT,
+            SYNTHETIC_ALT,
+        )));
+        pos = next_pos;
+    }
+
+    let mut file = String::new();
+    for record in records {
+        file.push_str(&record.to_string());
+        file.push_str("\r\n");
+    }
+    file
+}
+
+/// Parse every line of `text`, panicking if any of them fail.
+#[cfg(test)]
+pub(crate) fn parse_records(text: &str) -> Vec<Record<'_>> {
+    RecordReader::new(text)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Parse every line of `text`, panicking if any of them fail, and keep only the B records.
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn parse_fixes(text: &str) -> Vec<BRecord<'_>> {
+    parse_records(text)
+        .into_iter()
+        .filter_map(Record::into_b)
+        .collect()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthesized_file_parses() {
+        let start = "5152265N00032642W".parse::<RawPosition>().unwrap();
+        let file = synthesize(Manufacturer::Flarm, start.clone(), 100);
+        assert!(file.starts_with(
+            "AFLASYN\r\nHFDTE010620\r\nB1200005152265N00032642WA0100001000\r\n"
+        ));
+
+        let fixes = RecordReader::new(&file)
+            .filter_map(|record| match record.unwrap() {
+                Record::B(fix) => Some(fix),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fixes.len(), 100);
+        assert_eq!(fixes[0].pos, start);
+        assert_eq!(fixes[99].timestamp, Time::from_hms(12, 1, 39));
+
+        for pair in fixes.windows(2) {
+            let step = pair[0].pos.haversine_distance_m(&pair[1].pos);
+            assert!((step - SYNTHETIC_STEP_M).abs() < 3.);
+        }
+    }
+
+    #[test]
+    fn no_fixes() {
+        let start = "5152265N00032642W".parse::<RawPosition>().unwrap();
+        assert_eq!(
+            synthesize(Manufacturer::LxNav, start, 0),
+            "ALXVSYN\r\nHFDTE010620\r\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn single_char_manufacturer() {
+        let start = "5152265N00032642W".parse::<RawPosition>().unwrap();
+        synthesize(Manufacturer::UnknownSingle(b'X'), start, 1);
+    }
+}
@@ -0,0 +1,136 @@
+//! Reading records from sources of lines, such as files
+
+use std::io::{self, BufRead};
+use std::iter;
+
+use crate::records::OwnedRecord;
+use crate::util::ParseError;
+
+/// Read and parse every line from `reader`, yielding each record in its owned form.
+///
+/// Each line is read into a fresh `String`, which a borrowed `Record` can't outlive. Converting
+/// each record to an `OwnedRecord` as soon as it is parsed means the records can be collected or
+/// kept around. Lines may end in any of `\r\n`, `\n` or a lone `\r`, as with
+/// `records::split_lines`. Errors reading a line, including a line which isn't valid UTF-8, are
+/// returned as `ParseError::IOError`, and a line which fails to parse yields its error without
+/// ending the iteration.
+///
+/// ```
+/// # use igc::{io::parse_reader, records::Record};
+/// let file = "HFDTE140718\r\nLFoo the bar\r\n";
+/// let records = parse_reader(file.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// match records[1].record().unwrap() {
+///     Record::L(rec) => assert_eq!(rec.log_string, "Foo the bar"),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn parse_reader<R: BufRead>(
+    mut reader: R,
+) -> impl Iterator<Item = Result<OwnedRecord, ParseError>> {
+    iter::from_fn(move || read_line(&mut reader).transpose()).map(|line| {
+        let line = String::from_utf8(line?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        line.parse()
+    })
+}
+
+/// Read the bytes of the next line from `reader`, without its line ending, or `None` once the
+/// reader is exhausted.
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut line = Vec::new();
+    loop {
+        let (ending, used) = {
+            let available = reader.fill_buf()?;
+            if available.is_empty() {
+                return Ok(if line.is_empty() { None } else { Some(line) });
+            }
+            match available.iter().position(|&b| b == b'\r' || b == b'\n') {
+                Some(end) => {
+                    line.extend_from_slice(&available[..end]);
+                    (Some(available[end]), end + 1)
+                }
+                None => {
+                    line.extend_from_slice(available);
+                    (None, available.len())
+                }
+            }
+        };
+        reader.consume(used);
+
+        match ending {
+            Some(b'\r') => {
+                if reader.fill_buf()?.first() == Some(&b'\n') {
+                    reader.consume(1);
+                }
+                return Ok(Some(line));
+            }
+            Some(_) => return Ok(Some(line)),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::Record;
+
+    #[test]
+    fn collects_owned_records() {
+        let file = include_str!("../examples/example.igc");
+        let records = parse_reader(file.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), file.lines().count());
+        for (record, line) in records.iter().zip(file.lines()) {
+            assert_eq!(record.line(), line);
+            assert_eq!(record.record().unwrap(), Record::parse_line(line).unwrap());
+        }
+    }
+
+    #[test]
+    fn splits_any_line_ending() {
+        let file = "HFDTE140718\rLFoo\r\nLBar\nLBaz\r";
+        let records = parse_reader(file.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let lines = records.iter().map(OwnedRecord::line).collect::<Vec<_>>();
+        assert_eq!(lines, vec!["HFDTE140718", "LFoo", "LBar", "LBaz"]);
+    }
+
+    #[test]
+    fn splits_lines_across_buffer_boundaries() {
+        let file = "HFDTE140718\r\nLFoo\rLBar\n";
+        let records = parse_reader(io::BufReader::with_capacity(3, file.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let lines = records.iter().map(OwnedRecord::line).collect::<Vec<_>>();
+        assert_eq!(lines, vec!["HFDTE140718", "LFoo", "LBar"]);
+    }
+
+    #[test]
+    fn yields_errors() {
+        let file: &[u8] = b"B123\nLFoo\n\xff\xfe\nLBar\n";
+        let results = parse_reader(file).collect::<Vec<_>>();
+
+        assert_eq!(results.len(), 4);
+        match &results[0] {
+            Err(ParseError::TooShort {
+                expected: 35,
+                got: 4,
+            }) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(results[1].is_ok());
+        match &results[2] {
+            Err(ParseError::IOError(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(results[3].is_ok());
+    }
+}
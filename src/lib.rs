@@ -4,10 +4,29 @@
 //! minimize the number of heap allocations made during parsing.
 //! It is intended to be used as an unopinionated base for building higher level data structures
 //! representing traces/tasks/etc..
+//!
+//! The record parsers only need `core` and `alloc`, so the crate can be built without its default
+//! `std` feature for use on embedded targets. Doing so removes anything which needs I/O or
+//! floating point maths: the `analysis`, `export`, `fixtures` and `io` modules, the geodesy
+//! methods on `RawPosition`, and the `IOError` variant of `ParseError`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 #[cfg(test)]
 #[macro_use]
 extern crate proptest;
 
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(any(feature = "gpx", feature = "kml"))]
+pub mod export;
+#[cfg(any(feature = "std", test))]
+pub mod fixtures;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod lint;
+pub mod prelude;
 pub mod records;
 pub mod util;
@@ -0,0 +1,740 @@
+//! Checks of a parsed file's conformance to the IGC specification
+//!
+//! The record parsers are deliberately lenient, accepting anything they can make sense of. This
+//! module instead looks at the file as a whole, flagging the things a strict consumer (such as a
+//! competition scorer) would reject.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::records::{BRecord, Extendable, IRecord, JRecord, Record};
+use crate::util::{crosses_midnight, Manufacturer, ParseError, Time};
+
+/// Whether a fix at `time` goes backwards from one at `last_time`, other than by crossing
+/// midnight.
+fn goes_backwards(last_time: Time, time: Time) -> bool {
+    time < last_time && !crosses_midnight(last_time, time)
+}
+
+/// Whether the structural fields of `record` are all ASCII.
+///
+/// Free text, as given by `HasFreeText::free_text`, isn't checked: as with
+/// `ParseOptions::allow_latin1_text`, it is commonly written in other character sets.
+fn structure_is_ascii(record: &Record) -> bool {
+    match record {
+        Record::A(rec) => {
+            let manufacturer = match rec.manufacturer {
+                Manufacturer::UnknownSingle(byte) => byte.is_ascii(),
+                Manufacturer::UnknownTriple(triple) => triple.is_ascii(),
+                _ => true,
+            };
+            manufacturer
+                && rec.unique_id.is_ascii()
+                && rec.id_extension.map_or(true, str::is_ascii)
+        }
+        Record::B(rec) => rec.extension_string().is_ascii(),
+        Record::CDeclaration(_) | Record::CTurnpoint(_) | Record::L(_) => true,
+        Record::D(rec) => rec.station_id.is_ascii(),
+        Record::E(rec) => rec.mnemonic.is_ascii(),
+        Record::F(rec) => rec.satellites.as_str().is_ascii(),
+        Record::G(rec) => rec.data.is_ascii(),
+        Record::H(rec) => {
+            rec.mnemonic.is_ascii() && rec.friendly_name.map_or(true, str::is_ascii)
+        }
+        Record::I(IRecord(defs)) | Record::J(JRecord(defs)) => {
+            defs.extensions.iter().all(|ext| ext.mnemonic.is_ascii())
+        }
+        Record::K(rec) => rec.extension_string().is_ascii(),
+        Record::Unrecognised(line) => line.is_ascii(),
+    }
+}
+
+/// How serious a lint finding is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The file is unusual, but most consumers will cope with it.
+    Warning,
+
+    /// The file violates the specification.
+    Error,
+}
+
+/// The specific problem behind a lint finding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// There is no A record.
+    MissingARecord,
+
+    /// The A record is not the first record in the file.
+    ARecordNotFirst,
+
+    /// There is no HxDTE header giving the date of the flight.
+    MissingDate,
+
+    /// A B record has extensions, but no I record appears before it.
+    ExtensionsWithoutIRecord,
+
+    /// A K record has extensions, but no J record appears before it.
+    ExtensionsWithoutJRecord,
+
+    /// A fix's timestamp is earlier than that of the fix before it, other than by crossing
+    /// midnight.
+    NonMonotonicTime,
+
+    /// There is no G record.
+    MissingSecurityRecord,
+
+    /// A task declaration isn't followed by `turnpoint_count + 4` turnpoints.
+    WrongTurnpointCount,
+
+    /// A record contains non-ASCII characters outside of its free text.
+    NonAscii,
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            LintKind::MissingARecord => "No A record found",
+            LintKind::ARecordNotFirst => "A record is not the first record",
+            LintKind::MissingDate => "No DTE header found",
+            LintKind::ExtensionsWithoutIRecord => {
+                "B record extensions before any I record"
+            }
+            LintKind::ExtensionsWithoutJRecord => {
+                "K record extensions before any J record"
+            }
+            LintKind::NonMonotonicTime => "Fix timestamp goes backwards",
+            LintKind::MissingSecurityRecord => "No G record found",
+            LintKind::WrongTurnpointCount => {
+                "Wrong number of turnpoints for task declaration"
+            }
+            LintKind::NonAscii => "Non-ASCII characters found",
+        };
+
+        write!(f, "{}", message)
+    }
+}
+
+/// A single problem found by `lint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LintFinding {
+    pub severity: Severity,
+    pub kind: LintKind,
+
+    /// Index into the linted records of the offending record, if the problem is with a specific
+    /// record rather than something missing from the file.
+    pub record_index: Option<usize>,
+}
+
+impl LintFinding {
+    fn new(severity: Severity, kind: LintKind, record_index: Option<usize>) -> Self {
+        LintFinding {
+            severity,
+            kind,
+            record_index,
+        }
+    }
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        match self.record_index {
+            Some(index) => write!(f, "{} (record {}): {}", severity, index, self.kind),
+            None => write!(f, "{}: {}", severity, self.kind),
+        }
+    }
+}
+
+/// Check all the records of a file, in order, against the specification.
+///
+/// Findings are returned in the order of the records they refer to, followed by those about
+/// records missing from the file. Missing extension definitions are only reported for the first
+/// record that needs them.
+///
+/// ```
+/// # use igc::{lint::{lint, LintKind}, records::Record};
+/// let records = ["ACAMWatFoo", "HFDTE140718", "B0941145152265N00032642WA0011500115"]
+///     .iter()
+///     .map(|line| Record::parse_line(line).unwrap())
+///     .collect::<Vec<_>>();
+/// let findings = lint(&records);
+/// assert_eq!(findings.len(), 1);
+/// assert_eq!(findings[0].kind, LintKind::MissingSecurityRecord);
+/// ```
+pub fn lint(records: &[Record]) -> Vec<LintFinding> {
+    use self::LintKind::*;
+    use self::Severity::*;
+
+    let mut findings = Vec::new();
+
+    let mut seen_a = false;
+    let mut seen_date = false;
+    let mut seen_g = false;
+    let mut seen_i = false;
+    let mut seen_j = false;
+    let mut reported_i = false;
+    let mut reported_j = false;
+    let mut last_time = None;
+
+    for (index, record) in records.iter().enumerate() {
+        match record {
+            Record::A(_) => {
+                if index != 0 {
+                    findings.push(LintFinding::new(Error, ARecordNotFirst, Some(index)));
+                }
+                seen_a = true;
+            }
+            Record::B(fix) => {
+                if !seen_i && !reported_i && !fix.extension_string().is_empty() {
+                    findings.push(LintFinding::new(
+                        Error,
+                        ExtensionsWithoutIRecord,
+                        Some(index),
+                    ));
+                    reported_i = true;
+                }
+
+                let time = fix.timestamp;
+                if let Some(last_time) = last_time {
+                    if goes_backwards(last_time, time) {
+                        findings.push(LintFinding::new(
+                            Warning,
+                            NonMonotonicTime,
+                            Some(index),
+                        ));
+                    }
+                }
+                last_time = Some(time);
+            }
+            Record::CDeclaration(declaration) if declaration.turnpoint_count >= 0 => {
+                let expected = declaration.turnpoint_count as usize + 4;
+                let actual = records[index + 1..]
+                    .iter()
+                    .take_while(|rec| match rec {
+                        Record::CTurnpoint(_) => true,
+                        _ => false,
+                    })
+                    .count();
+                if actual != expected {
+                    findings.push(LintFinding::new(
+                        Error,
+                        WrongTurnpointCount,
+                        Some(index),
+                    ));
+                }
+            }
+            Record::G(_) => seen_g = true,
+            Record::H(header) if header.mnemonic == "DTE" => seen_date = true,
+            Record::I(_) => seen_i = true,
+            Record::J(_) => seen_j = true,
+            Record::K(rec)
+                if !seen_j && !reported_j && !rec.extension_string().is_empty() =>
+            {
+                findings.push(LintFinding::new(
+                    Error,
+                    ExtensionsWithoutJRecord,
+                    Some(index),
+                ));
+                reported_j = true;
+            }
+            _ => {}
+        }
+
+        if !structure_is_ascii(record) {
+            findings.push(LintFinding::new(Error, NonAscii, Some(index)));
+        }
+    }
+
+    if !seen_a {
+        findings.push(LintFinding::new(Error, MissingARecord, None));
+    }
+    if !seen_date {
+        findings.push(LintFinding::new(Error, MissingDate, None));
+    }
+    if !seen_g {
+        findings.push(LintFinding::new(Error, MissingSecurityRecord, None));
+    }
+
+    findings
+}
+
+/// A problem with the overall structure of a file, as found by `validate_structure`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StructureError {
+    /// There is no A record.
+    MissingARecord,
+
+    /// A G record comes before a B record, so can't cover it.
+    SecurityBeforeFixes { record_index: usize },
+
+    /// A fix's timestamp is earlier than that of the fix before it, other than by crossing
+    /// midnight.
+    NonMonotonicFixes { record_index: usize },
+
+    /// An extension defined by an I record runs past the end of a B record it applies to. The
+    /// index is that of the shortest such B record.
+    ExtensionBeyondFix { record_index: usize },
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StructureError::MissingARecord => write!(f, "No A record found"),
+            StructureError::SecurityBeforeFixes { record_index } => {
+                write!(f, "G record before B record {}", record_index)
+            }
+            StructureError::NonMonotonicFixes { record_index } => {
+                write!(f, "Fix timestamp goes backwards at record {}", record_index)
+            }
+            StructureError::ExtensionBeyondFix { record_index } => write!(
+                f,
+                "I record extension runs past the end of B record {}",
+                record_index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StructureError {}
+
+/// Cheaply check the overall structure of a file before trusting it.
+///
+/// This is a subset of the checks made by `lint`, plus checking that the G record comes after
+/// every fix and that every fix is long enough for the extensions defined by the I record before
+/// it. The first problem found is returned, in the order listed by `StructureError`.
+///
+/// ```
+/// # use igc::{lint::{validate_structure, StructureError}, records::Record};
+/// let records = ["ACAMWatFoo", "GABCDEF", "B0941145152265N00032642WA0011500115"]
+///     .iter()
+///     .map(|line| Record::parse_line(line).unwrap())
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     validate_structure(&records),
+///     Err(StructureError::SecurityBeforeFixes { record_index: 2 })
+/// );
+/// ```
+pub fn validate_structure(records: &[Record]) -> Result<(), StructureError> {
+    let fixes = records
+        .iter()
+        .enumerate()
+        .filter_map(|(index, record)| match record {
+            Record::B(fix) => Some((index, fix)),
+            _ => None,
+        });
+
+    if !records.iter().any(|record| match record {
+        Record::A(_) => true,
+        _ => false,
+    }) {
+        return Err(StructureError::MissingARecord);
+    }
+
+    let first_g = records.iter().position(|record| match record {
+        Record::G(_) => true,
+        _ => false,
+    });
+    if let Some(first_g) = first_g {
+        if let Some((index, _)) = fixes.clone().find(|&(index, _)| index > first_g) {
+            return Err(StructureError::SecurityBeforeFixes {
+                record_index: index,
+            });
+        }
+    }
+
+    let mut last_time = None;
+    for (index, fix) in fixes {
+        let time = fix.timestamp;
+        if let Some(last_time) = last_time {
+            if goes_backwards(last_time, time) {
+                return Err(StructureError::NonMonotonicFixes {
+                    record_index: index,
+                });
+            }
+        }
+        last_time = Some(time);
+    }
+
+    // The furthest byte needed by the I record in force, and the shortest fix it applies to
+    let mut needed = 0;
+    let mut shortest: Option<(usize, usize)> = None;
+    for (index, record) in records.iter().enumerate() {
+        match record {
+            Record::I(i_record) => {
+                needed = i_record
+                    .extensions
+                    .iter()
+                    .map(|ext| ext.range.end_byte as usize)
+                    .max()
+                    .unwrap_or(0);
+            }
+            Record::B(fix) if needed > 0 => {
+                let length = BRecord::BASE_LENGTH + fix.extension_string().len();
+                if length < needed && shortest.map_or(true, |(_, min)| length < min) {
+                    shortest = Some((index, length));
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some((index, _)) = shortest {
+        return Err(StructureError::ExtensionBeyondFix {
+            record_index: index,
+        });
+    }
+
+    Ok(())
+}
+
+/// An error yielded by `MonotonicFixChecker`.
+#[derive(Debug)]
+pub enum FixOrderError {
+    /// The wrapped iterator failed to parse a record.
+    Parse(ParseError),
+
+    /// A fix's timestamp is earlier than that of the fix before it, other than by crossing
+    /// midnight.
+    Backwards { previous: Time, time: Time },
+
+    /// A fix has the same timestamp as the fix before it.
+    Duplicate { time: Time },
+}
+
+impl fmt::Display for FixOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FixOrderError::Parse(error) => fmt::Display::fmt(error, f),
+            FixOrderError::Backwards { previous, time } => {
+                write!(
+                    f,
+                    "Fix timestamp goes backwards from {} to {}",
+                    previous, time
+                )
+            }
+            FixOrderError::Duplicate { time } => {
+                write!(f, "Duplicate fix timestamp {}", time)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FixOrderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FixOrderError::Parse(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Checks the order of fixes as records are streamed, without buffering the file.
+///
+/// Wraps an iterator of parsed records, such as a `RecordReader`, passing every record through.
+/// Whenever a fix's timestamp goes backwards or repeats that of the previous fix, an extra
+/// error is yielded just before the fix itself. A large step backwards, such as from `235900`
+/// to `000100`, is taken to be the flight crossing midnight rather than an error.
+///
+/// ```
+/// # use igc::{lint::{FixOrderError, MonotonicFixChecker}, records::RecordReader};
+/// let file = "B1200015152265N00032642WA0011500115\nB1200005152265N00032642WA0011500115\n";
+/// let results = MonotonicFixChecker::new(RecordReader::new(file)).collect::<Vec<_>>();
+/// assert_eq!(results.len(), 3);
+/// match results[1] {
+///     Err(FixOrderError::Backwards { .. }) => {}
+///     _ => unreachable!(),
+/// }
+/// assert!(results[2].is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MonotonicFixChecker<'a, I> {
+    records: I,
+    last_time: Option<Time>,
+    pending: Option<Record<'a>>,
+}
+
+impl<'a, I> MonotonicFixChecker<'a, I>
+where
+    I: Iterator<Item = Result<Record<'a>, ParseError>>,
+{
+    pub fn new(records: I) -> Self {
+        MonotonicFixChecker {
+            records,
+            last_time: None,
+            pending: None,
+        }
+    }
+}
+
+impl<'a, I> Iterator for MonotonicFixChecker<'a, I>
+where
+    I: Iterator<Item = Result<Record<'a>, ParseError>>,
+{
+    type Item = Result<Record<'a>, FixOrderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(record) = self.pending.take() {
+            return Some(Ok(record));
+        }
+
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(FixOrderError::Parse(error))),
+        };
+
+        let time = match &record {
+            Record::B(fix) => fix.timestamp,
+            _ => return Some(Ok(record)),
+        };
+        let previous = self.last_time.replace(time);
+
+        let error = match previous {
+            Some(previous) if previous == time => FixOrderError::Duplicate { time },
+            Some(previous) if goes_backwards(previous, time) => {
+                FixOrderError::Backwards { previous, time }
+            }
+            _ => return Some(Ok(record)),
+        };
+
+        self.pending = Some(record);
+        Some(Err(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_lines(lines: &[&str]) -> Vec<(LintKind, Option<usize>)> {
+        let records = lines
+            .iter()
+            .map(|line| Record::parse_line(line).unwrap())
+            .collect::<Vec<_>>();
+        lint(&records)
+            .into_iter()
+            .map(|finding| (finding.kind, finding.record_index))
+            .collect()
+    }
+
+    #[test]
+    fn conformant_file() {
+        let lines = [
+            "ACAMWatFoo",
+            "HFDTE140718",
+            "I013638ENL",
+            "J010812HDT",
+            "C230718092044000000000200",
+            "C5156040N00038120WTakeoff",
+            "C5156040N00038120WStart",
+            "C5156040N00038120WFinish",
+            "C5156040N00038120WLanding",
+            "B2359595152265N00032642WA0011500115123",
+            "K000000123",
+            "B0000005152265N00032642WA0011500115123",
+            "GABCDEF",
+        ];
+        assert!(lint_lines(&lines).is_empty());
+    }
+
+    #[test]
+    fn empty_file() {
+        assert_eq!(
+            lint_lines(&[]),
+            vec![
+                (LintKind::MissingARecord, None),
+                (LintKind::MissingDate, None),
+                (LintKind::MissingSecurityRecord, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn misplaced_records() {
+        let lines = [
+            "HFDTE140718",
+            "ACAMWatFoo",
+            "B1200005152265N00032642WA0011500115123",
+            "B1200015152265N00032642WA0011500115123",
+            "K120001123",
+            "B1159595152265N00032642WA0011500115",
+            "GABCDEF",
+        ];
+        assert_eq!(
+            lint_lines(&lines),
+            vec![
+                (LintKind::ARecordNotFirst, Some(1)),
+                (LintKind::ExtensionsWithoutIRecord, Some(2)),
+                (LintKind::ExtensionsWithoutJRecord, Some(4)),
+                (LintKind::NonMonotonicTime, Some(5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_turnpoint_count() {
+        let lines = [
+            "ACAMWatFoo",
+            "HFDTE140718",
+            "C230718092044000000000201",
+            "C5156040N00038120WTakeoff",
+            "C5156040N00038120WStart",
+            "C5156040N00038120WFinish",
+            "C5156040N00038120WLanding",
+            // Filser loggers write a count of -2 when no task is declared
+            "C1005091201531005090001-2",
+            "GABCDEF",
+        ];
+        assert_eq!(
+            lint_lines(&lines),
+            vec![(LintKind::WrongTurnpointCount, Some(2))]
+        );
+    }
+
+    #[test]
+    fn non_ascii() {
+        let lines = [
+            "ACAMWatFoo",
+            "HFDTE140718",
+            "HFPLTPILÖT:Zoe",
+            "HFPLTPILOT:Zoë",
+            "LFoo the bär",
+            "GABCDEF",
+        ];
+        assert_eq!(lint_lines(&lines), vec![(LintKind::NonAscii, Some(2))]);
+    }
+
+    fn validate_lines(lines: &[&str]) -> Result<(), StructureError> {
+        let records = lines
+            .iter()
+            .map(|line| Record::parse_line(line).unwrap())
+            .collect::<Vec<_>>();
+        validate_structure(&records)
+    }
+
+    #[test]
+    fn valid_structure() {
+        let lines = [
+            "ACAMWatFoo",
+            "HFDTE140718",
+            "I013638ENL",
+            "B2359595152265N00032642WA0011500115123",
+            "LComment",
+            "B0000005152265N00032642WA0011500115123",
+            "GABCDEF",
+            "LNot covered by the security record",
+        ];
+        assert_eq!(validate_lines(&lines), Ok(()));
+    }
+
+    #[test]
+    fn structure_errors() {
+        assert_eq!(
+            validate_lines(&["HFDTE140718", "B1200005152265N00032642WA0011500115"]),
+            Err(StructureError::MissingARecord)
+        );
+
+        assert_eq!(
+            validate_lines(&[
+                "ACAMWatFoo",
+                "B1200005152265N00032642WA0011500115",
+                "GABCDEF",
+                "B1200015152265N00032642WA0011500115",
+            ]),
+            Err(StructureError::SecurityBeforeFixes { record_index: 3 })
+        );
+
+        assert_eq!(
+            validate_lines(&[
+                "ACAMWatFoo",
+                "B1200005152265N00032642WA0011500115",
+                "B1159595152265N00032642WA0011500115",
+            ]),
+            Err(StructureError::NonMonotonicFixes { record_index: 2 })
+        );
+
+        assert_eq!(
+            validate_lines(&[
+                "ACAMWatFoo",
+                "B1200005152265N00032642WA0011500115",
+                "I013638ENL",
+                "B1200015152265N00032642WA0011500115123",
+                "B1200025152265N00032642WA00115001151",
+                "B1200035152265N00032642WA001150011512",
+            ]),
+            Err(StructureError::ExtensionBeyondFix { record_index: 4 })
+        );
+    }
+
+    #[test]
+    fn finding_display() {
+        let finding = LintFinding::new(Severity::Error, LintKind::NonAscii, Some(3));
+        assert_eq!(
+            finding.to_string(),
+            "error (record 3): Non-ASCII characters found"
+        );
+    }
+
+    /// Stream `file` through a `MonotonicFixChecker`, summarising each item as the timestamp of
+    /// a fix, "record" for any other record, or the error.
+    fn check_order(file: &str) -> Vec<String> {
+        use crate::records::RecordReader;
+
+        MonotonicFixChecker::new(RecordReader::new(file))
+            .map(|result| match result {
+                Ok(Record::B(fix)) => fix.timestamp.to_string(),
+                Ok(_) => "record".to_string(),
+                Err(FixOrderError::Parse(_)) => "parse".to_string(),
+                Err(FixOrderError::Backwards { previous, time }) => {
+                    format!("backwards {} {}", previous, time)
+                }
+                Err(FixOrderError::Duplicate { time }) => format!("duplicate {}", time),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn monotonic_checker_clean_trace() {
+        let file = "HFDTE140718\n\
+                    B1200005152265N00032642WA0011500115\n\
+                    LFoo\n\
+                    B1200015152265N00032642WA0011500115\n";
+        assert_eq!(
+            check_order(file),
+            vec!["record", "120000", "record", "120001"]
+        );
+    }
+
+    #[test]
+    fn monotonic_checker_backwards_and_duplicate() {
+        let file = "B1200015152265N00032642WA0011500115\n\
+                    B1200005152265N00032642WA0011500115\n\
+                    B1200005152265N00032642WA0011500115\n\
+                    B123\n\
+                    B1200025152265N00032642WA0011500115\n";
+        assert_eq!(
+            check_order(file),
+            vec![
+                "120001",
+                "backwards 120001 120000",
+                "120000",
+                "duplicate 120000",
+                "120000",
+                "parse",
+                "120002",
+            ]
+        );
+    }
+
+    #[test]
+    fn monotonic_checker_midnight_crossing() {
+        let file = "B2359005152265N00032642WA0011500115\n\
+                    B0001005152265N00032642WA0011500115\n";
+        assert_eq!(check_order(file), vec!["235900", "000100"]);
+    }
+}
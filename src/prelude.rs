@@ -0,0 +1,34 @@
+//! Convenient re-exports of the most commonly used types and traits
+//!
+//! This brings in `Record` and every record type, `RecordReader`, `Headers`, the traits which
+//! add methods to records (`Extendable` and `HasFreeText`), and the time, date, position and
+//! error types from `util`. More specialised items, such as the security record validators,
+//! still need importing from their own modules.
+//!
+//! ```
+//! use igc::prelude::*;
+//!
+//! let file = "HFDTE230718\nB0941145152265N00032642WA0011500115\n";
+//! for record in RecordReader::new(file) {
+//!     match record.unwrap() {
+//!         Record::H(header) => {
+//!             assert_eq!(header.as_flight_date(), Some(Date::from_dmy(23, 7, 18)));
+//!         }
+//!         Record::B(fix) => {
+//!             assert_eq!(fix.timestamp, Time::from_hms(9, 41, 14));
+//!             assert_eq!(fix.pos, "5152265N00032642W".parse::<RawPosition>().unwrap());
+//!             assert_eq!(fix.extension_string(), "");
+//!         }
+//!         _ => unreachable!(),
+//!     }
+//! }
+//! ```
+
+pub use crate::records::{
+    ARecord, AltitudeSource, BRecord, CRecordDeclaration, CRecordTurnpoint, DRecord,
+    ERecord, Extendable, Extension, FRecord, FixValid, GRecord, HRecord, HasFreeText,
+    Headers, IRecord, JRecord, KRecord, LRecord, Record, RecordKind, RecordReader,
+};
+pub use crate::util::{
+    Compass, Date, Manufacturer, ParseError, RawLatitude, RawLongitude, RawPosition, Time,
+};
@@ -1,6 +1,6 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
 use crate::util::DisplayOption;
 use crate::util::Manufacturer;
@@ -42,7 +42,10 @@ impl<'a> ARecord<'a> {
         assert_eq!(&line[0..1], "A");
 
         if line.len() < 7 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 7,
+                got: line.len(),
+            });
         }
 
         // check for old spec format (e.g. `AC00069`)
@@ -153,6 +156,12 @@ mod tests {
             ARecord::new(Manufacturer::Filser, "01460", Some("FLIGHT:1"))
         );
 
+        // XCTrack appends a long hexadecimal ID
+        assert_eq!(
+            ARecord::parse("AXCT7f3a9b21c4d5e6f7").unwrap(),
+            ARecord::new(Manufacturer::XcTrack, "7f3", Some("a9b21c4d5e6f7"))
+        );
+
         assert_eq!(
             ARecord::parse("AX00000").unwrap(),
             ARecord::new(Manufacturer::UnknownSingle(b'X'), "00000", None)
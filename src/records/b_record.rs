@@ -1,12 +1,14 @@
+use alloc::string::{String, ToString};
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-use crate::records::extension::Extendable;
+use crate::records::extension::{Extendable, Extension, ExtensionView};
+use crate::records::line_at;
 use crate::util::{ParseError, RawPosition, Time};
 
 /// Possible values for the "fix valid" field of a B record
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
@@ -17,6 +19,31 @@ pub enum FixValid {
     NavWarning,
 }
 
+impl FixValid {
+    /// Whether this is a valid 3D fix, rather than one with a navigation warning.
+    ///
+    /// ```
+    /// # use igc::records::FixValid;
+    /// assert!(FixValid::Valid.is_valid());
+    /// assert!(!FixValid::NavWarning.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        *self == FixValid::Valid
+    }
+}
+
+/// Which of a fix's two altitudes to use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum AltitudeSource {
+    Pressure,
+    Gps,
+}
+
 /// A Fix record
 ///
 /// Only the fields for { timestamp, lat, lon, fix_valid, pressure_altitude, gps_altitude} are stored.
@@ -37,16 +64,46 @@ pub struct BRecord<'a> {
 }
 
 impl<'a> BRecord<'a> {
+    /// Create a fix with no extensions.
+    pub fn new(
+        timestamp: Time,
+        pos: RawPosition,
+        fix_valid: FixValid,
+        pressure_alt: i16,
+        gps_alt: i16,
+    ) -> Self {
+        BRecord {
+            timestamp,
+            pos,
+            fix_valid,
+            pressure_alt,
+            gps_alt,
+            extension_string: "",
+        }
+    }
+
     /// Parse an IGC B record string.
     ///
+    /// Each of the two five character altitude fields is a signed integer, zero padded to the
+    /// left (e.g. `00115` or `-0116`). Some older loggers pad with spaces instead (e.g. ` -116`),
+    /// which is also accepted, though such a record will be written back out zero padded.
+    ///
+    /// Every field up to and including the GPS altitude must be present, making 35 characters
+    /// the minimum, and anything shorter is `ParseError::TooShort`. Some older loggers stop
+    /// after the pressure altitude; such a record gives `ParseError::MissingGpsAltitude` instead
+    /// so that the legacy format can be detected.
+    ///
     /// ```
     /// # use igc::{ records::BRecord, util::Time };
     /// let record = BRecord::parse("B0941145152265N00032642WA0011500115").unwrap();
     /// assert_eq!(record.timestamp, Time::from_hms(9, 41, 14));
     /// ```
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
-        if line.len() < Self::BASE_LENGTH {
-            return Err(ParseError::SyntaxError);
+        if line.len() < LEGACY_LENGTH {
+            return Err(ParseError::TooShort {
+                expected: Self::BASE_LENGTH,
+                got: line.len(),
+            });
         }
         if !line.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
@@ -61,8 +118,11 @@ impl<'a> BRecord<'a> {
             _ => return Err(ParseError::SyntaxError),
         };
 
-        let pressure_alt = line[25..30].parse::<i16>()?;
-        let gps_alt = line[30..35].parse::<i16>()?;
+        let pressure_alt = parse_altitude(&line[25..30])?;
+        if line.len() < Self::BASE_LENGTH {
+            return Err(ParseError::MissingGpsAltitude);
+        }
+        let gps_alt = parse_altitude(&line[30..35])?;
 
         let extension_string = &line[35..];
 
@@ -75,6 +135,176 @@ impl<'a> BRecord<'a> {
             extension_string,
         })
     }
+
+    /// Parse a B record beginning at byte offset `start` of `input`, returning it along with the
+    /// number of bytes consumed, including the line terminator if there is one.
+    ///
+    /// ```
+    /// # use igc::records::BRecord;
+    /// let input = "42: B0941145152265N00032642WA0011500115\r\n";
+    /// let (record, consumed) = BRecord::parse_at(input, 4).unwrap();
+    /// assert_eq!(record.gps_alt, 115);
+    /// assert_eq!(4 + consumed, input.len());
+    /// ```
+    pub fn parse_at(input: &'a str, start: usize) -> Result<(Self, usize), ParseError> {
+        let (line, consumed) = line_at(input, start)?;
+        Ok((Self::parse(line)?, consumed))
+    }
+
+    /// Copy this record into one which owns its extensions, so it can outlive the line it was
+    /// parsed from.
+    ///
+    /// ```
+    /// # use igc::records::{BRecord, BRecordBuf};
+    /// let fix: BRecordBuf = {
+    ///     let line = String::from("B0941145152265N00032642WA0011500115012");
+    ///     BRecord::parse(&line).unwrap().into_owned()
+    /// };
+    /// assert_eq!(fix.as_record().to_string(), "B0941145152265N00032642WA0011500115012");
+    /// ```
+    pub fn into_owned(self) -> BRecordBuf {
+        BRecordBuf {
+            timestamp: self.timestamp,
+            pos: self.pos,
+            fix_valid: self.fix_valid,
+            pressure_alt: self.pressure_alt,
+            gps_alt: self.gps_alt,
+            extension_string: self.extension_string.to_string(),
+        }
+    }
+
+    /// The pressure or GPS altitude in meters, as chosen by `source`.
+    ///
+    /// ```
+    /// # use igc::records::{AltitudeSource, BRecord};
+    /// let record = BRecord::parse("B0941145152265N00032642WA0011500120").unwrap();
+    /// assert_eq!(record.altitude(AltitudeSource::Pressure), 115);
+    /// assert_eq!(record.altitude(AltitudeSource::Gps), 120);
+    /// ```
+    pub fn altitude(&self, source: AltitudeSource) -> i16 {
+        match source {
+            AltitudeSource::Pressure => self.pressure_alt,
+            AltitudeSource::Gps => self.gps_alt,
+        }
+    }
+
+    /// The pressure altitude in meters.
+    ///
+    /// This is the same as `pressure_alt`, as IGC altitudes are always in meters.
+    pub fn pressure_alt_meters(&self) -> i16 {
+        self.pressure_alt
+    }
+
+    /// The GPS altitude in meters.
+    ///
+    /// This is the same as `gps_alt`, as IGC altitudes are always in meters.
+    pub fn gps_alt_meters(&self) -> i16 {
+        self.gps_alt
+    }
+
+    /// Whether the pressure altitude was actually recorded, given the extensions defined in the
+    /// file's I record.
+    ///
+    /// Loggers without a pressure sensor zero fill the field, so a bare zero is ambiguous on its
+    /// own. It is only taken to be a genuine reading if an extension that also needs a pressure
+    /// sensor (airspeed or variometer) is declared in `defs` and present in this record.
+    ///
+    /// ```
+    /// # use igc::records::{BRecord, IRecord};
+    /// let record = BRecord::parse("B0941145152265N00032642WA0000000115000").unwrap();
+    /// assert!(!record.has_pressure_alt(&[]));
+    ///
+    /// let i_record = IRecord::parse("I013638VAT").unwrap();
+    /// assert!(record.has_pressure_alt(&i_record.extensions));
+    /// ```
+    pub fn has_pressure_alt(&self, defs: &[Extension]) -> bool {
+        let extensions = self.extensions(defs);
+        self.pressure_alt != 0
+            || PRESSURE_SENSOR_MNEMONICS
+                .iter()
+                .any(|mnemonic| extensions.get(mnemonic).is_some())
+    }
+
+    /// Whether the GPS altitude was actually recorded, given the extensions defined in the
+    /// file's I record.
+    ///
+    /// Loggers zero fill the field when they have no 3D fix, so a bare zero is ambiguous on its
+    /// own. As with `has_pressure_alt`, it is only taken to be a genuine reading if a vertical
+    /// fix accuracy (VXA) is declared in `defs` and present in this record.
+    pub fn has_gps_alt(&self, defs: &[Extension]) -> bool {
+        self.gps_alt != 0 || self.extensions(defs).get("VXA").is_some()
+    }
+
+    /// The estimated horizontal fix accuracy in meters, from the FXA extension.
+    ///
+    /// Returns `None` if FXA isn't defined in `defs`, or isn't present in this record as a
+    /// number.
+    ///
+    /// ```
+    /// # use igc::records::{BRecord, IRecord};
+    /// let i_record = IRecord::parse("I023638FXA3940SIU").unwrap();
+    /// let record = BRecord::parse("B0941145152265N00032642WA001150011501208").unwrap();
+    /// assert_eq!(record.fix_accuracy(&i_record.extensions), Some(12));
+    /// assert_eq!(record.satellites_in_use(&i_record.extensions), Some(8));
+    /// ```
+    pub fn fix_accuracy(&self, defs: &[Extension]) -> Option<u16> {
+        self.extensions(defs).get("FXA")?.parse().ok()
+    }
+
+    /// The number of satellites used for the fix, from the SIU extension.
+    ///
+    /// Returns `None` if SIU isn't defined in `defs`, or isn't present in this record as a
+    /// number.
+    pub fn satellites_in_use(&self, defs: &[Extension]) -> Option<u8> {
+        self.extensions(defs).get("SIU")?.parse().ok()
+    }
+
+    /// Get a view of this record's extensions, as laid out by the extensions defined in the
+    /// file's I record.
+    ///
+    /// ```
+    /// # use igc::records::{BRecord, IRecord};
+    /// let i_record = IRecord::parse("I023638FXA3941ENL").unwrap();
+    /// let record = BRecord::parse("B0941145152265N00032642WA0011500115012345").unwrap();
+    /// let extensions = record.extensions(&i_record.extensions);
+    /// assert_eq!(extensions.get("ENL"), Some("345"));
+    /// ```
+    pub fn extensions<'r, 'd>(
+        &'r self,
+        defs: &'d [Extension<'d>],
+    ) -> ExtensionView<'r, 'd, Self> {
+        ExtensionView::new(self, defs)
+    }
+}
+
+/// The length of a B record from older loggers which stops after the pressure altitude.
+const LEGACY_LENGTH: usize = 30;
+
+/// Extensions which can only be recorded by a logger with a pressure sensor.
+///
+/// The specification doesn't say which extensions need one; this is a heuristic based on the
+/// airspeed and variometer extensions it defines, and loggers may well record these by other
+/// means.
+const PRESSURE_SENSOR_MNEMONICS: [&str; 3] = ["IAS", "TAS", "VAT"];
+
+/// Parse an altitude field, allowing for it being padded with leading spaces.
+///
+/// A field which isn't a number is a `SyntaxError`, while a number too large for an `i16` is
+/// `NumberOutOfRange`.
+fn parse_altitude(field: &str) -> Result<i16, ParseError> {
+    let field = field.trim_start_matches(' ');
+    let digits = if field.starts_with('-') || field.starts_with('+') {
+        &field[1..]
+    } else {
+        field
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::SyntaxError);
+    }
+
+    field
+        .parse::<i16>()
+        .map_err(|_| ParseError::NumberOutOfRange)
 }
 
 impl<'a> Extendable for BRecord<'a> {
@@ -85,6 +315,45 @@ impl<'a> Extendable for BRecord<'a> {
     }
 }
 
+/// An owned version of `BRecord`, which doesn't borrow from the line it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct BRecordBuf {
+    pub timestamp: Time,
+    pub pos: RawPosition,
+    pub fix_valid: FixValid,
+    pub pressure_alt: i16,
+    pub gps_alt: i16,
+    extension_string: String,
+}
+
+impl BRecordBuf {
+    /// Borrow this as a `BRecord`, for use with the rest of the API.
+    pub fn as_record(&self) -> BRecord<'_> {
+        BRecord {
+            timestamp: self.timestamp,
+            pos: self.pos.clone(),
+            fix_valid: self.fix_valid,
+            pressure_alt: self.pressure_alt,
+            gps_alt: self.gps_alt,
+            extension_string: &self.extension_string,
+        }
+    }
+}
+
+impl<'a> From<BRecord<'a>> for BRecordBuf {
+    fn from(record: BRecord<'a>) -> Self {
+        record.into_owned()
+    }
+}
+
+#[cfg(feature = "geo")]
+impl<'a> From<&BRecord<'a>> for geo_types::Point<f64> {
+    fn from(record: &BRecord<'a>) -> Self {
+        (&record.pos).into()
+    }
+}
+
 impl<'a> fmt::Display for BRecord<'a> {
     /// Formats this record as it should appear in an IGC file.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -110,7 +379,7 @@ impl<'a> fmt::Display for BRecord<'a> {
 mod tests {
     use super::*;
 
-    use crate::records::extension::Extension;
+    use crate::records::extension::{Extension, ExtensionRange};
     use crate::util::{Compass, RawLatitude, RawLongitude, RawPosition, Time};
 
     #[test]
@@ -139,6 +408,149 @@ mod tests {
         assert_eq!(parsed_record, expected);
     }
 
+    #[test]
+    fn parse_space_padded_altitudes() {
+        // As written by some older Filser units
+        let record = BRecord::parse("B0941145152265N00032642WA  115 -116").unwrap();
+        assert_eq!(record.pressure_alt, 115);
+        assert_eq!(record.gps_alt, -116);
+        assert_eq!(record.pressure_alt_meters(), 115);
+        assert_eq!(record.gps_alt_meters(), -116);
+        assert_eq!(format!("{}", record), "B0941145152265N00032642WA00115-0116");
+
+        let record = BRecord::parse("B0941145152265N00032642WA    0-0000").unwrap();
+        assert_eq!(record.pressure_alt, 0);
+        assert_eq!(record.gps_alt, 0);
+
+        assert!(BRecord::parse("B0941145152265N00032642WA     00115").is_err());
+        assert!(BRecord::parse("B0941145152265N00032642WA 11 500115").is_err());
+        assert!(BRecord::parse("B0941145152265N00032642WA- 11500115").is_err());
+    }
+
+    #[test]
+    fn altitude_errors() {
+        for line in &[
+            "B0941145152265N00032642WAABCDE00115",
+            "B0941145152265N00032642WA00115-----",
+            "B0941145152265N00032642WA001150011x",
+        ] {
+            assert!(match BRecord::parse(line) {
+                Err(ParseError::SyntaxError) => true,
+                _ => false,
+            });
+        }
+
+        for line in &[
+            "B0941145152265N00032642WA9999900115",
+            "B0941145152265N00032642WA0011532768",
+        ] {
+            assert!(match BRecord::parse(line) {
+                Err(ParseError::NumberOutOfRange) => true,
+                _ => false,
+            });
+        }
+
+        let record = BRecord::parse("B0941145152265N00032642WA32767-9999").unwrap();
+        assert_eq!(record.pressure_alt, 32_767);
+        assert_eq!(record.gps_alt, -9_999);
+    }
+
+    #[test]
+    fn altitude_presence() {
+        let defs = [Extension::new("VXA", 36, 38), Extension::new("TAS", 39, 41)];
+
+        let record = BRecord::parse("B0941145152265N00032642WA0011500116").unwrap();
+        assert!(record.has_pressure_alt(&[]));
+        assert!(record.has_gps_alt(&[]));
+
+        let record = BRecord::parse("B0941145152265N00032642WA0000000000").unwrap();
+        assert!(!record.has_pressure_alt(&[]));
+        assert!(!record.has_gps_alt(&[]));
+        // The extensions are declared, but missing from this record
+        assert!(!record.has_pressure_alt(&defs));
+        assert!(!record.has_gps_alt(&defs));
+
+        let record = BRecord::parse("B0941145152265N00032642WA0000000000003").unwrap();
+        assert!(!record.has_pressure_alt(&defs));
+        assert!(record.has_gps_alt(&defs));
+
+        let record = BRecord::parse("B0941145152265N00032642WA0000000000003050").unwrap();
+        assert!(record.has_pressure_alt(&defs));
+        assert!(record.has_gps_alt(&defs));
+    }
+
+    #[test]
+    fn fix_quality_extensions() {
+        let defs = [Extension::new("FXA", 36, 38), Extension::new("SIU", 39, 40)];
+
+        let record = BRecord::parse("B0941145152265N00032642WA001150011501208").unwrap();
+        assert_eq!(record.fix_accuracy(&defs), Some(12));
+        assert_eq!(record.satellites_in_use(&defs), Some(8));
+        assert_eq!(record.fix_accuracy(&defs[1..]), None);
+        assert_eq!(record.satellites_in_use(&defs[..1]), None);
+
+        // SIU is truncated
+        let record = BRecord::parse("B0941145152265N00032642WA0011500115012").unwrap();
+        assert_eq!(record.fix_accuracy(&defs), Some(12));
+        assert_eq!(record.satellites_in_use(&defs), None);
+
+        let record = BRecord::parse("B0941145152265N00032642WA00115001150X2 8").unwrap();
+        assert_eq!(record.fix_accuracy(&defs), None);
+        assert_eq!(record.satellites_in_use(&defs), None);
+    }
+
+    #[test]
+    fn into_owned_round_trip() {
+        let line = "B0941145152265N00032642WA00115-0116FooExtensionString";
+        let record = BRecord::parse(line).unwrap();
+        let owned = BRecordBuf::from(record.clone());
+
+        assert_eq!(owned.pressure_alt, 115);
+        assert_eq!(owned.as_record(), record);
+    }
+
+    #[test]
+    fn parse_legacy_without_gps_alt() {
+        let line = "B0941145152265N00032642WA00115";
+        assert_eq!(line.len(), 30);
+        assert!(match BRecord::parse(line) {
+            Err(ParseError::MissingGpsAltitude) => true,
+            _ => false,
+        });
+
+        // A partial GPS altitude is no better
+        assert!(match BRecord::parse("B0941145152265N00032642WA0011500") {
+            Err(ParseError::MissingGpsAltitude) => true,
+            _ => false,
+        });
+
+        // Too short to even hold the pressure altitude, or broken before it
+        assert!(match BRecord::parse("B0941145152265N00032642WA0011") {
+            Err(ParseError::TooShort {
+                expected: 35,
+                got: 29,
+            }) => true,
+            _ => false,
+        });
+        assert!(match BRecord::parse("B0941145152265N00032642WX00115") {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn fix_valid_serde_names() {
+        assert_eq!(
+            serde_json::to_string(&FixValid::Valid).unwrap(),
+            "\"valid\""
+        );
+        assert_eq!(
+            serde_json::to_string(&FixValid::NavWarning).unwrap(),
+            "\"navwarning\""
+        );
+    }
+
     #[test]
     fn parse_with_invalid_char_boundary() {
         assert!(BRecord::parse("B🌀®0  A¡𞤀𐘀 𐀀a0⮘ ে").is_err());
@@ -178,16 +590,39 @@ mod tests {
         };
 
         let extension = Extension {
-            start_byte: 36,
-            end_byte: 40,
+            range: ExtensionRange {
+                start_byte: 36,
+                end_byte: 40,
+            },
             mnemonic: "FOO",
         };
 
-        let extracted = record.get_extension(&extension).unwrap();
+        let extracted = record.get_extension(extension.range).unwrap();
         let expected = "01234";
         assert_eq!(extracted, expected);
     }
 
+    #[test]
+    fn brecord_extension_view() {
+        let record =
+            BRecord::parse("B0941145152265N00032642WA0011500115012345678").unwrap();
+        let defs = [
+            Extension::new("FXA", 36, 38),
+            Extension::new("ENL", 39, 41),
+            Extension::new("TAS", 42, 46),
+        ];
+        let extensions = record.extensions(&defs);
+
+        assert_eq!(extensions.get("FXA"), Some("012"));
+        assert_eq!(extensions.get("ENL"), Some("345"));
+        assert_eq!(extensions.get("TAS"), None);
+        assert_eq!(extensions.get("SIU"), None);
+        assert_eq!(
+            extensions.iter().collect::<Vec<_>>(),
+            vec![("FXA", "012"), ("ENL", "345")]
+        );
+    }
+
     proptest! {
         #[test]
         #[allow(unused_must_use)]
@@ -1,7 +1,11 @@
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
+#[cfg(feature = "std")]
+use std::io;
 
+use crate::records::{HasFreeText, Record};
 use crate::util::{Date, DisplayOption, ParseError, RawPosition, Time};
 
 /// The first flavor of C Record - a task record which defines some properties of the whole task.
@@ -14,9 +18,14 @@ use crate::util::{Date, DisplayOption, ParseError, RawPosition, Time};
 pub struct CRecordDeclaration<'a> {
     pub date: Date,
     pub time: Time,
+    /// The intended date of the flight, or `None` if the logger zero filled it (`000000`).
+    ///
+    /// The field must still be present: a declaration shortened to leave it out can't be told
+    /// apart from one with a task name, so is `ParseError::TooShort` if it is left out.
     pub flight_date: Option<Date>,
     pub task_id: u16,
     pub turnpoint_count: i8,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub task_name: Option<&'a str>,
 }
 
@@ -34,7 +43,10 @@ impl<'a> CRecordDeclaration<'a> {
     /// ```
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         if line.len() < 25 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 25,
+                got: line.len(),
+            });
         }
         if !line.bytes().take(25).all(|b| b.is_ascii()) {
             return Err(ParseError::NonASCIICharacters);
@@ -67,6 +79,12 @@ impl<'a> CRecordDeclaration<'a> {
     }
 }
 
+impl<'a> HasFreeText for CRecordDeclaration<'a> {
+    fn free_text(&self) -> Option<&str> {
+        self.task_name
+    }
+}
+
 impl<'a> fmt::Display for CRecordDeclaration<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let flight_date = match &self.flight_date {
@@ -92,6 +110,7 @@ impl<'a> fmt::Display for CRecordDeclaration<'a> {
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct CRecordTurnpoint<'a> {
     pub position: RawPosition,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub turnpoint_name: Option<&'a str>,
 }
 
@@ -107,7 +126,10 @@ impl<'a> CRecordTurnpoint<'a> {
     /// ```
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         if line.len() < 18 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 18,
+                got: line.len(),
+            });
         }
         if !line.bytes().take(18).all(|b| b.is_ascii()) {
             return Err(ParseError::NonASCIICharacters);
@@ -129,6 +151,12 @@ impl<'a> CRecordTurnpoint<'a> {
     }
 }
 
+impl<'a> HasFreeText for CRecordTurnpoint<'a> {
+    fn free_text(&self) -> Option<&str> {
+        self.turnpoint_name
+    }
+}
+
 impl<'a> fmt::Display for CRecordTurnpoint<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -140,9 +168,216 @@ impl<'a> fmt::Display for CRecordTurnpoint<'a> {
     }
 }
 
+/// A task declaration along with the points making up the task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Task<'a> {
+    pub declaration: CRecordDeclaration<'a>,
+    pub takeoff: CRecordTurnpoint<'a>,
+    pub start: CRecordTurnpoint<'a>,
+    pub turnpoints: Vec<CRecordTurnpoint<'a>>,
+    pub finish: CRecordTurnpoint<'a>,
+    pub landing: CRecordTurnpoint<'a>,
+}
+
+impl<'a> Task<'a> {
+    /// Assemble a task from a task declaration and the turnpoint records following it.
+    ///
+    /// `records` must start with the declaration, which has to be followed by exactly
+    /// `turnpoint_count + 4` turnpoint records. Any records after those are ignored. If the
+    /// declaration has a negative turnpoint count (Filser loggers write `-2` when no task was
+    /// declared), there is no task and `None` is returned.
+    ///
+    /// ```
+    /// # use igc::records::{Record, Task};
+    /// let records = [
+    ///     "C230718092044000000000200",
+    ///     "C5156040N00038120WTakeoff",
+    ///     "C5156040N00038120WStart",
+    ///     "C5156040N00038120WFinish",
+    ///     "C5156040N00038120WLanding",
+    /// ]
+    /// .iter()
+    /// .map(|line| Record::parse_line(line).unwrap())
+    /// .collect::<Vec<_>>();
+    ///
+    /// let task = Task::from_records(&records).unwrap().unwrap();
+    /// assert_eq!(task.start.turnpoint_name, Some("Start"));
+    /// assert!(task.turnpoints.is_empty());
+    /// ```
+    pub fn from_records(records: &[Record<'a>]) -> Result<Option<Self>, ParseError> {
+        let declaration = match records.first() {
+            Some(Record::CDeclaration(declaration)) => declaration.clone(),
+            _ => return Err(ParseError::SyntaxError),
+        };
+
+        if declaration.turnpoint_count < 0 {
+            return Ok(None);
+        }
+        let count =
+            declaration.turnpoint_count as usize + TaskDeclaration::EXTRA_TURNPOINTS;
+
+        let mut points = Vec::with_capacity(count);
+        for record in records[1..].iter().take(count) {
+            match record {
+                Record::CTurnpoint(turnpoint) => points.push(turnpoint.clone()),
+                _ => return Err(ParseError::SyntaxError),
+            }
+        }
+        if points.len() < count {
+            return Err(ParseError::SyntaxError);
+        }
+
+        let landing = points.pop().unwrap();
+        let finish = points.pop().unwrap();
+        let mut points = points.into_iter();
+        let takeoff = points.next().unwrap();
+        let start = points.next().unwrap();
+
+        Ok(Some(Task {
+            declaration,
+            takeoff,
+            start,
+            turnpoints: points.collect(),
+            finish,
+            landing,
+        }))
+    }
+
+    /// The scoring points of the task in order: the start, each turnpoint, and the finish.
+    #[cfg(feature = "std")]
+    fn scoring_points(&self) -> impl Iterator<Item = &CRecordTurnpoint<'a>> {
+        core::iter::once(&self.start)
+            .chain(self.turnpoints.iter())
+            .chain(core::iter::once(&self.finish))
+    }
+
+    /// The great circle length in meters of each leg of the task, from the start through each
+    /// turnpoint to the finish. The takeoff and landing points aren't included.
+    #[cfg(feature = "std")]
+    pub fn leg_distances_m(&self) -> Vec<f64> {
+        let points = self.scoring_points().collect::<Vec<_>>();
+        points
+            .windows(2)
+            .map(|leg| leg[0].position.haversine_distance_m(&leg[1].position))
+            .collect()
+    }
+
+    /// The total great circle length in meters of the task, as the sum of `leg_distances_m`.
+    #[cfg(feature = "std")]
+    pub fn total_distance_m(&self) -> f64 {
+        self.leg_distances_m().iter().sum()
+    }
+}
+
+/// Builds a task declaration along with its turnpoints, keeping `turnpoint_count` consistent with
+/// the turnpoints given.
+///
+/// Turnpoints should be added in the order they appear in the file: takeoff, start, each turn
+/// point, finish, and finally landing.
+///
+/// ```
+/// # use igc::{ records::TaskDeclaration, util::{Date, RawPosition, Time} };
+/// let airfield = "5156040N00038120W".parse::<RawPosition>().unwrap();
+/// let turnpoint = "5151000N00030000W".parse::<RawPosition>().unwrap();
+/// let task = TaskDeclaration::new(Date::from_dmy(23, 7, 18), Time::from_hms(9, 20, 44), 1);
+/// let (declaration, turnpoints) = task
+///     .add_turnpoint("Takeoff", airfield.clone())
+///     .add_turnpoint("Start", airfield.clone())
+///     .add_turnpoint("Turn", turnpoint)
+///     .add_turnpoint("Finish", airfield.clone())
+///     .add_turnpoint("Landing", airfield)
+///     .build()
+///     .unwrap();
+/// assert_eq!(declaration.turnpoint_count, 1);
+/// assert_eq!(turnpoints.len(), 5);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskDeclaration<'a> {
+    date: Date,
+    time: Time,
+    flight_date: Option<Date>,
+    task_id: u16,
+    task_name: Option<&'a str>,
+    turnpoints: Vec<CRecordTurnpoint<'a>>,
+}
+
+impl<'a> TaskDeclaration<'a> {
+    /// The takeoff, start, finish and landing points, which aren't counted in `turnpoint_count`.
+    pub const EXTRA_TURNPOINTS: usize = 4;
+
+    pub fn new(date: Date, time: Time, task_id: u16) -> Self {
+        TaskDeclaration {
+            date,
+            time,
+            flight_date: None,
+            task_id,
+            task_name: None,
+            turnpoints: Vec::new(),
+        }
+    }
+
+    /// Set the intended date of the flight.
+    pub fn flight_date(mut self, flight_date: Date) -> Self {
+        self.flight_date = Some(flight_date);
+        self
+    }
+
+    pub fn task_name(mut self, task_name: &'a str) -> Self {
+        self.task_name = Some(task_name);
+        self
+    }
+
+    /// Append the next point of the task. An empty name is omitted from the record.
+    pub fn add_turnpoint(mut self, name: &'a str, position: RawPosition) -> Self {
+        self.turnpoints.push(CRecordTurnpoint {
+            position,
+            turnpoint_name: if name.is_empty() { None } else { Some(name) },
+        });
+        self
+    }
+
+    /// Build the declaration record and its turnpoints.
+    ///
+    /// Fails if fewer than `EXTRA_TURNPOINTS` points were added, or if there are too many for
+    /// the two digit `turnpoint_count` field.
+    pub fn build(
+        &self,
+    ) -> Result<(CRecordDeclaration<'a>, Vec<CRecordTurnpoint<'a>>), ParseError> {
+        if self.turnpoints.len() < Self::EXTRA_TURNPOINTS {
+            return Err(ParseError::SyntaxError);
+        }
+        let turnpoint_count = self.turnpoints.len() - Self::EXTRA_TURNPOINTS;
+        if turnpoint_count > 99 {
+            return Err(ParseError::NumberOutOfRange);
+        }
+
+        let declaration = CRecordDeclaration {
+            date: self.date,
+            time: self.time,
+            flight_date: self.flight_date,
+            task_id: self.task_id,
+            turnpoint_count: turnpoint_count as i8,
+            task_name: self.task_name,
+        };
+        Ok((declaration, self.turnpoints.clone()))
+    }
+
+    /// Write the declaration and each of its turnpoints, one CRLF terminated line per record.
+    #[cfg(feature = "std")]
+    pub fn write<W: io::Write>(&self, out: &mut W) -> Result<(), ParseError> {
+        let (declaration, turnpoints) = self.build()?;
+        write!(out, "{}\r\n", declaration)?;
+        for turnpoint in turnpoints {
+            write!(out, "{}\r\n", turnpoint)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::parse_records;
     use crate::util::{Compass, RawLatitude, RawLongitude, RawPosition};
 
     #[test]
@@ -150,8 +385,8 @@ mod tests {
         let sample_string = "C230718092044000000000204Foo task";
         let parsed_declaration = CRecordDeclaration::parse(sample_string).unwrap();
         let mut expected = CRecordDeclaration {
-            date: Date::from_dmy(23, 07, 18),
-            time: Time::from_hms(09, 20, 44),
+            date: Date::from_dmy(23, 7, 18),
+            time: Time::from_hms(9, 20, 44),
             flight_date: None,
             task_id: 2,
             turnpoint_count: 4,
@@ -165,6 +400,26 @@ mod tests {
         assert_eq!(parsed_declaration, expected);
     }
 
+    #[test]
+    fn c_record_declaration_flight_date() {
+        let declared = CRecordDeclaration::parse("C230718092044230718000204").unwrap();
+        assert_eq!(declared.flight_date, Some(Date::from_dmy(23, 7, 18)));
+        assert_eq!(declared.to_string(), "C230718092044230718000204");
+
+        let undeclared = CRecordDeclaration::parse("C230718092044000000000204").unwrap();
+        assert_eq!(undeclared.flight_date, None);
+        assert_eq!(undeclared.to_string(), "C230718092044000000000204");
+
+        // Shortened to leave the flight date out entirely
+        assert!(match CRecordDeclaration::parse("C230718092044000204") {
+            Err(ParseError::TooShort {
+                expected: 25,
+                got: 19,
+            }) => true,
+            _ => false,
+        });
+    }
+
     #[test]
     fn c_record_declaration_parse_with_missing_content() {
         assert!(CRecordDeclaration::parse("C").is_err());
@@ -179,8 +434,8 @@ mod tests {
     fn c_record_declaration_format() {
         let expected_string = "C230718092044000000000204Foo task";
         let mut declaration = CRecordDeclaration {
-            date: Date::from_dmy(23, 07, 18),
-            time: Time::from_hms(09, 20, 44),
+            date: Date::from_dmy(23, 7, 18),
+            time: Time::from_hms(9, 20, 44),
             flight_date: None,
             task_id: 2,
             turnpoint_count: 4,
@@ -197,9 +452,9 @@ mod tests {
     /// Filser LX5000 records `turnpoint_count: -2` when no task has been declared
     fn c_record_declaration_format_with_negative_tp_count() {
         let declaration = CRecordDeclaration {
-            date: Date::from_dmy(10, 05, 09),
-            time: Time::from_hms(12, 01, 53),
-            flight_date: Some(Date::from_dmy(10, 05, 09)),
+            date: Date::from_dmy(10, 5, 9),
+            time: Time::from_hms(12, 1, 53),
+            flight_date: Some(Date::from_dmy(10, 5, 9)),
             task_id: 1,
             turnpoint_count: -2,
             task_name: None,
@@ -222,6 +477,17 @@ mod tests {
         assert_eq!(parsed_turnpoint, expected);
     }
 
+    #[test]
+    fn c_record_turnpoint_round_trip_trailing_spaces() {
+        let sample_string = "C5156040N00038120WLBZ-Leighton Buzzard NE   ";
+        let parsed_turnpoint = CRecordTurnpoint::parse(sample_string).unwrap();
+        assert_eq!(
+            parsed_turnpoint.turnpoint_name,
+            Some("LBZ-Leighton Buzzard NE   ")
+        );
+        assert_eq!(format!("{}", parsed_turnpoint), sample_string);
+    }
+
     #[test]
     fn c_record_turnpoint_parse_with_missing_content() {
         assert!(CRecordTurnpoint::parse("C").is_err());
@@ -232,6 +498,161 @@ mod tests {
         assert!(CRecordTurnpoint::parse("C𑠀𖭽ₐ𞸧\u{1daa1}").is_err());
     }
 
+    fn task(turnpoints: usize) -> TaskDeclaration<'static> {
+        let position = "5156040N00038120W".parse::<RawPosition>().unwrap();
+        (0..turnpoints).fold(
+            TaskDeclaration::new(Date::from_dmy(23, 7, 18), Time::from_hms(9, 20, 44), 2),
+            |task, _| task.add_turnpoint("LBZ", position.clone()),
+        )
+    }
+
+    #[test]
+    fn task_declaration_turnpoint_count() {
+        for turnpoints in 4..104 {
+            let (declaration, records) = task(turnpoints).build().unwrap();
+            assert_eq!(declaration.turnpoint_count as usize, turnpoints - 4);
+            assert_eq!(records.len(), turnpoints);
+        }
+
+        for &turnpoints in &[0, 3, 104] {
+            assert!(task(turnpoints).build().is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn task_declaration_write() {
+        let mut out = Vec::new();
+        task(4)
+            .task_name("Foo task")
+            .flight_date(Date::from_dmy(24, 7, 18))
+            .add_turnpoint("", "5151000N00030000W".parse().unwrap())
+            .write(&mut out)
+            .unwrap();
+
+        let lines = String::from_utf8(out).unwrap();
+        assert_eq!(
+            lines,
+            "C230718092044240718000201Foo task\r\n\
+             C5156040N00038120WLBZ\r\n\
+             C5156040N00038120WLBZ\r\n\
+             C5156040N00038120WLBZ\r\n\
+             C5156040N00038120WLBZ\r\n\
+             C5151000N00030000W\r\n"
+        );
+
+        for line in lines.lines() {
+            assert_eq!(Record::parse_line(line).unwrap().to_string(), line);
+        }
+    }
+
+    #[test]
+    fn task_from_records() {
+        let records = parse_records(
+            "C230718092044000000000202Foo task\n\
+             C5156040N00038120WTakeoff\n\
+             C5156040N00038120WStart\n\
+             C5151000N00030000WTP1\n\
+             C5151000N00030000WTP2\n\
+             C5156040N00038120WFinish\n\
+             C5156040N00038120WLanding\n\
+             LFoo the bar\n",
+        );
+
+        let task = Task::from_records(&records).unwrap().unwrap();
+        assert_eq!(task.declaration.task_name, Some("Foo task"));
+        assert_eq!(task.takeoff.turnpoint_name, Some("Takeoff"));
+        assert_eq!(task.start.turnpoint_name, Some("Start"));
+        assert_eq!(
+            task.turnpoints
+                .iter()
+                .map(|tp| tp.turnpoint_name.unwrap())
+                .collect::<Vec<_>>(),
+            vec!["TP1", "TP2"]
+        );
+        assert_eq!(task.finish.turnpoint_name, Some("Finish"));
+        assert_eq!(task.landing.turnpoint_name, Some("Landing"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn task_distances() {
+        use approx::assert_relative_eq;
+
+        let records = parse_records(
+            "C230718092044000000000202Triangle\n\
+             C5200000N00100000WTakeoff\n\
+             C5100000N00000000EStart\n\
+             C5200000N00000000ETP1\n\
+             C5200000N00100000ETP2\n\
+             C5100000N00000000EFinish\n\
+             C5200000N00100000WLanding\n",
+        );
+        let task = Task::from_records(&records).unwrap().unwrap();
+
+        let point = |line: &str| line.parse::<RawPosition>().unwrap();
+        let start = point("5100000N00000000E");
+        let tp1 = point("5200000N00000000E");
+        let tp2 = point("5200000N00100000E");
+        let expected = [
+            start.haversine_distance_m(&tp1),
+            tp1.haversine_distance_m(&tp2),
+            tp2.haversine_distance_m(&start),
+        ];
+
+        let legs = task.leg_distances_m();
+        assert_eq!(legs.len(), 3);
+        for (leg, expected) in legs.iter().zip(expected.iter()) {
+            assert_relative_eq!(*leg, *expected);
+        }
+        // One degree of latitude on the first leg
+        assert_relative_eq!(legs[0], 111_195., epsilon = 1.);
+        assert_relative_eq!(task.total_distance_m(), expected.iter().sum::<f64>());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn task_distance_without_turnpoints() {
+        let records = parse_records(
+            "C230718092044000000000200\n\
+             C5156040N00038120WTakeoff\n\
+             C5156040N00038120WStart\n\
+             C5156040N00038120WFinish\n\
+             C5156040N00038120WLanding\n",
+        );
+        let task = Task::from_records(&records).unwrap().unwrap();
+
+        assert_eq!(task.leg_distances_m(), vec![0.]);
+        assert_eq!(task.total_distance_m(), 0.);
+    }
+
+    #[test]
+    fn task_from_truncated_records() {
+        let records = parse_records(
+            "C230718092044000000000201\n\
+             C5156040N00038120WTakeoff\n\
+             C5156040N00038120WStart\n\
+             C5151000N00030000WTP1\n\
+             C5156040N00038120WFinish\n\
+             LFoo the bar\n\
+             C5156040N00038120WLanding\n",
+        );
+
+        assert!(Task::from_records(&records).is_err());
+        assert!(Task::from_records(&records[..5]).is_err());
+        assert!(Task::from_records(&records[1..]).is_err());
+        assert!(Task::from_records(&[]).is_err());
+    }
+
+    #[test]
+    fn task_from_records_without_task() {
+        let records = parse_records(
+            "C1005091201531005090001-2\n\
+             LFoo the bar\n",
+        );
+        assert_eq!(Task::from_records(&records).unwrap(), None);
+    }
+
     proptest! {
         #[test]
         #[allow(unused_must_use)]
@@ -244,5 +665,11 @@ mod tests {
         fn parse_turnpoint_doesnt_crash(s in "C\\PC*") {
             CRecordTurnpoint::parse(&s);
         }
+
+        #[test]
+        fn turnpoint_round_trip(s in "C[0-8][0-9][0-5][0-9]{4}[NS]0[0-7][0-9][0-5][0-9]{4}[EW][ -~]*") {
+            let parsed = CRecordTurnpoint::parse(&s).unwrap();
+            prop_assert_eq!(format!("{}", parsed), s);
+        }
     }
 }
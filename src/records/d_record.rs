@@ -1,6 +1,6 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
 use crate::util::ParseError;
 
@@ -20,25 +20,36 @@ pub enum GpsQualifier {
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct DRecord<'a> {
     pub qualifier: GpsQualifier,
+    /// The DGPS station ID, which takes up four characters in the record. A shorter ID is
+    /// padded with trailing spaces when written out, which are trimmed when parsing.
     pub station_id: &'a str,
 }
 
 impl<'a> DRecord<'a> {
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
-        if line.len() != 6 {
-            return Err(ParseError::SyntaxError);
+        if line.len() < 6 {
+            return Err(ParseError::TooShort {
+                expected: 6,
+                got: line.len(),
+            });
         }
-
         let bytes = line.as_bytes();
         assert_eq!(bytes[0], b'D');
 
+        if line.len() != 6 {
+            return Err(ParseError::SyntaxError);
+        }
+        if !line.is_ascii() {
+            return Err(ParseError::NonASCIICharacters);
+        }
+
         let qualifier = match bytes[1] {
             b'1' => GpsQualifier::Gps,
             b'2' => GpsQualifier::DGps,
             _ => return Err(ParseError::SyntaxError),
         };
 
-        let station_id = &line[2..6];
+        let station_id = line[2..6].trim_end_matches(' ');
 
         Ok(DRecord {
             qualifier,
@@ -54,13 +65,14 @@ impl<'a> fmt::Display for DRecord<'a> {
             GpsQualifier::DGps => '2',
         };
 
-        write!(f, "D{}{}", qual_str, self.station_id)
+        write!(f, "D{}{:<4}", qual_str, self.station_id)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{DRecord, GpsQualifier};
+    use crate::util::ParseError;
 
     #[test]
     fn drecord_parse() {
@@ -74,6 +86,28 @@ mod tests {
         assert_eq!(parsed, expected);
     }
 
+    #[test]
+    fn drecord_parse_wrong_length() {
+        for line in &["", "D", "D1", "D1ABC"] {
+            assert!(match DRecord::parse(line) {
+                Err(ParseError::TooShort { expected: 6, .. }) => true,
+                _ => false,
+            });
+        }
+        assert!(match DRecord::parse("D1ABCDE") {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn drecord_parse_non_ascii() {
+        assert!(match DRecord::parse("D1A\u{e9}B") {
+            Err(ParseError::NonASCIICharacters) => true,
+            _ => false,
+        });
+    }
+
     #[test]
     fn drecord_format() {
         let expected_string = "D1ABCD";
@@ -85,6 +119,25 @@ mod tests {
         assert_eq!(format!("{}", record), expected_string);
     }
 
+    #[test]
+    fn drecord_format_dgps_short_station_id() {
+        // A two character station ID is padded out to the full width
+        let record = DRecord {
+            qualifier: GpsQualifier::DGps,
+            station_id: "AB",
+        };
+
+        assert_eq!(format!("{}", record), "D2AB  ");
+        assert_eq!(DRecord::parse(&record.to_string()).unwrap(), record);
+    }
+
+    #[test]
+    fn drecord_round_trip() {
+        for line in &["D1ABCD", "D20042"] {
+            assert_eq!(DRecord::parse(line).unwrap().to_string(), *line);
+        }
+    }
+
     proptest! {
         #[test]
         #[allow(unused_must_use)]
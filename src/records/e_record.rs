@@ -1,9 +1,66 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
+use crate::records::HasFreeText;
 use crate::util::{DisplayOption, ParseError, Time};
 
+/// The kind of an event, as given by the mnemonic of its E record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum EventKind<'a> {
+    /// `ATS` - the altimeter pressure setting.
+    AltimeterSetting,
+    /// `BFI` - blind flying instrument in use.
+    BlindFlyingInstrument,
+    /// `CGD` - change of geodetic datum.
+    ChangeOfGeodeticDatum,
+    /// `EOF` - engine off.
+    EngineOff,
+    /// `EON` - engine on.
+    EngineOn,
+    /// `FIN` - finish.
+    Finish,
+    /// `LAD` - last place of the flight's declared task achieved.
+    LastPlaceAchieved,
+    /// `ONT` - on task, having started the declared task.
+    OnTask,
+    /// `PEV` - a pilot initiated event, marked by pressing a button on the logger.
+    PilotEvent,
+    /// `STA` - start.
+    Start,
+    /// `TPC` - turnpoint confirmation.
+    TurnpointConfirmation,
+    /// `UND` - undercarriage lowered or raised.
+    Undercarriage,
+    /// Any other mnemonic.
+    Unknown(&'a str),
+}
+
+impl<'a> EventKind<'a> {
+    pub fn from_mnemonic(mnemonic: &'a str) -> Self {
+        match mnemonic {
+            "ATS" => EventKind::AltimeterSetting,
+            "BFI" => EventKind::BlindFlyingInstrument,
+            "CGD" => EventKind::ChangeOfGeodeticDatum,
+            "EOF" => EventKind::EngineOff,
+            "EON" => EventKind::EngineOn,
+            "FIN" => EventKind::Finish,
+            "LAD" => EventKind::LastPlaceAchieved,
+            "ONT" => EventKind::OnTask,
+            "PEV" => EventKind::PilotEvent,
+            "STA" => EventKind::Start,
+            "TPC" => EventKind::TurnpointConfirmation,
+            "UND" => EventKind::Undercarriage,
+            _ => EventKind::Unknown(mnemonic),
+        }
+    }
+}
+
 /// Describes an event logged during the flight, associated with the B Record immediately
 /// following.
 ///
@@ -19,7 +76,10 @@ pub struct ERecord<'a> {
 impl<'a> ERecord<'a> {
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         if line.len() < 10 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 10,
+                got: line.len(),
+            });
         }
         if !line.bytes().take(10).all(|b| b.is_ascii()) {
             return Err(ParseError::NonASCIICharacters);
@@ -42,6 +102,17 @@ impl<'a> ERecord<'a> {
             text,
         })
     }
+
+    /// The kind of event, as given by the mnemonic.
+    pub fn event_kind(&self) -> EventKind<'a> {
+        EventKind::from_mnemonic(self.mnemonic)
+    }
+}
+
+impl<'a> HasFreeText for ERecord<'a> {
+    fn free_text(&self) -> Option<&str> {
+        self.text
+    }
 }
 
 impl<'a> fmt::Display for ERecord<'a> {
@@ -73,6 +144,32 @@ mod tests {
         assert_eq!(parsed, expected);
     }
 
+    #[test]
+    fn erecord_round_trip_trailing_spaces() {
+        let example_line = "E120515PEVPilot event  ";
+        let parsed = ERecord::parse(example_line).unwrap();
+        assert_eq!(parsed.text, Some("Pilot event  "));
+        assert_eq!(format!("{}", parsed), example_line);
+
+        let example_line = "E120515PEV ";
+        let parsed = ERecord::parse(example_line).unwrap();
+        assert_eq!(parsed.text, Some(" "));
+        assert_eq!(format!("{}", parsed), example_line);
+    }
+
+    #[test]
+    fn event_kind() {
+        let parsed = ERecord::parse("E120515PEV").unwrap();
+        assert_eq!(parsed.event_kind(), EventKind::PilotEvent);
+        assert_eq!(parsed.mnemonic, "PEV");
+
+        let parsed = ERecord::parse("E120515STA").unwrap();
+        assert_eq!(parsed.event_kind(), EventKind::Start);
+
+        let parsed = ERecord::parse("E120515FOOText").unwrap();
+        assert_eq!(parsed.event_kind(), EventKind::Unknown("FOO"));
+    }
+
     #[test]
     fn parse_with_invalid_char_boundary() {
         assert!(ERecord::parse("Eⶠ𑛀  ").is_err());
@@ -96,5 +193,11 @@ mod tests {
         fn parse_doesnt_crash(s in "E\\PC*") {
             ERecord::parse(&s);
         }
+
+        #[test]
+        fn round_trip(s in "E[01][0-9][0-5][0-9][0-5][0-9][A-Z]{3}[ -~]*") {
+            let parsed = ERecord::parse(&s).unwrap();
+            prop_assert_eq!(format!("{}", parsed), s);
+        }
     }
 }
@@ -1,18 +1,38 @@
+use alloc::vec::Vec;
+use core::{fmt, slice};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, str};
 
 use crate::util::ParseError;
 
-/// Defines a generic record extension, as appears in I and J records.
+/// The range of bytes occupied by an extension within a record.
 ///
 /// The start and end bytes are defined as being 1-indexed including the initial record type
-/// discrimination character.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// discrimination character, and the range is inclusive at both ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
-pub struct Extension<'a> {
+pub struct ExtensionRange {
     pub start_byte: u8,
     pub end_byte: u8,
+}
+
+impl ExtensionRange {
+    pub fn new(start_byte: u8, end_byte: u8) -> Self {
+        assert!(start_byte > 2);
+        assert!(end_byte >= start_byte);
+
+        ExtensionRange {
+            start_byte,
+            end_byte,
+        }
+    }
+}
+
+/// Defines a generic record extension, as appears in I and J records.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Extension<'a> {
+    pub range: ExtensionRange,
     pub mnemonic: &'a str,
 }
 
@@ -21,13 +41,10 @@ impl<'a> Extension<'a> {
 
     pub fn new(mnemonic: &'a str, start_byte: u8, end_byte: u8) -> Self {
         assert_eq!(mnemonic.len(), 3);
-        assert!(start_byte > 2);
-        assert!(end_byte > start_byte);
 
         Extension {
             mnemonic,
-            start_byte,
-            end_byte,
+            range: ExtensionRange::new(start_byte, end_byte),
         }
     }
 
@@ -53,11 +70,46 @@ impl<'a> Extension<'a> {
         let mnemonic = &string[4..7];
 
         Ok(Self {
-            start_byte,
-            end_byte,
+            range: ExtensionRange {
+                start_byte,
+                end_byte,
+            },
             mnemonic,
         })
     }
+
+    /// Get the value of this extension out of the whole line of the record it applies to.
+    ///
+    /// The 1-indexed byte range is applied directly to `full_line`, which must still start with
+    /// the record type character. This differs from `Extendable::get_extension`, which works on
+    /// the extension string left over after a record's fixed fields have been parsed.
+    ///
+    /// Returns `ParseError::MissingExtension` if the line is too short to contain the whole
+    /// range.
+    ///
+    /// ```
+    /// # use igc::records::Extension;
+    /// let fxa = Extension::parse("3638FXA").unwrap();
+    /// let line = "B0941145152265N00032642WA0011500115012345";
+    /// assert_eq!(fxa.slice(line).unwrap(), "012");
+    /// ```
+    pub fn slice<'l>(&self, full_line: &'l str) -> Result<&'l str, ParseError> {
+        if self.range.start_byte == 0 || self.range.end_byte < self.range.start_byte {
+            return Err(ParseError::BadExtension);
+        }
+
+        // The start/end bytes are specified as being 1-indexed
+        let start = self.range.start_byte as usize - 1;
+        let end = self.range.end_byte as usize;
+
+        if end > full_line.len() {
+            Err(ParseError::MissingExtension)
+        } else {
+            full_line
+                .get(start..end)
+                .ok_or(ParseError::NonASCIICharacters)
+        }
+    }
 }
 
 impl<'a> fmt::Display for Extension<'a> {
@@ -65,7 +117,7 @@ impl<'a> fmt::Display for Extension<'a> {
         write!(
             f,
             "{:02}{:02}{}",
-            self.start_byte, self.end_byte, self.mnemonic
+            self.range.start_byte, self.range.end_byte, self.mnemonic
         )
     }
 }
@@ -77,21 +129,23 @@ pub trait Extendable {
     fn extension_string(&self) -> &str;
 
     /// Get a given extension from the record implementing this trait.
-    fn get_extension<'a, 'b>(
-        &'a self,
-        extension: &'b Extension<'a>,
-    ) -> Result<&'a str, ParseError> {
-        if (extension.start_byte as usize) < Self::BASE_LENGTH {
+    ///
+    /// Returns `ParseError::MissingExtension` if the record is too short to contain the whole
+    /// range.
+    fn get_extension(&self, range: ExtensionRange) -> Result<&str, ParseError> {
+        if (range.start_byte as usize) <= Self::BASE_LENGTH
+            || range.end_byte < range.start_byte
+        {
             return Err(ParseError::BadExtension);
         }
 
         let ext_str = self.extension_string();
 
         // The start/end bytes are specified as being 1-indexed
-        let start = extension.start_byte as usize - Self::BASE_LENGTH - 1;
-        let end = extension.end_byte as usize - Self::BASE_LENGTH;
+        let start = range.start_byte as usize - Self::BASE_LENGTH - 1;
+        let end = range.end_byte as usize - Self::BASE_LENGTH;
 
-        if start >= ext_str.len() {
+        if end > ext_str.len() {
             Err(ParseError::MissingExtension)
         } else {
             Ok(&ext_str[start..end])
@@ -99,6 +153,65 @@ pub trait Extendable {
     }
 }
 
+/// A view of the extensions present in a record, as laid out by a set of extension definitions.
+///
+/// Definitions whose byte range runs past the end of the record are skipped.
+#[derive(Debug)]
+pub struct ExtensionView<'r, 'd, R: Extendable> {
+    record: &'r R,
+    defs: &'d [Extension<'d>],
+}
+
+impl<'r, 'd, R: Extendable> ExtensionView<'r, 'd, R> {
+    pub fn new(record: &'r R, defs: &'d [Extension<'d>]) -> Self {
+        Self { record, defs }
+    }
+
+    /// Get the value of the extension with the given mnemonic, if it is both defined and present.
+    pub fn get(&self, mnemonic: &str) -> Option<&'r str> {
+        self.defs
+            .iter()
+            .find(|ext| ext.mnemonic == mnemonic)
+            .and_then(|ext| self.record.get_extension(ext.range).ok())
+    }
+
+    /// Iterate over (mnemonic, value) pairs for every extension present in the record.
+    pub fn iter(&self) -> ExtensionViewIter<'r, 'd, R> {
+        ExtensionViewIter {
+            record: self.record,
+            defs: self.defs.iter(),
+        }
+    }
+}
+
+impl<'r, 'd, R: Extendable> IntoIterator for ExtensionView<'r, 'd, R> {
+    type Item = (&'d str, &'r str);
+    type IntoIter = ExtensionViewIter<'r, 'd, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct ExtensionViewIter<'r, 'd, R: Extendable> {
+    record: &'r R,
+    defs: slice::Iter<'d, Extension<'d>>,
+}
+
+impl<'r, 'd, R: Extendable> Iterator for ExtensionViewIter<'r, 'd, R> {
+    type Item = (&'d str, &'r str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.record;
+        self.defs.find_map(|ext| {
+            record
+                .get_extension(ext.range)
+                .ok()
+                .map(|value| (ext.mnemonic, value))
+        })
+    }
+}
+
 /// A record defining a set of extensions (either an I or a J record)
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
@@ -109,6 +222,25 @@ pub struct ExtensionDefRecord<'a> {
 }
 
 impl<'a> ExtensionDefRecord<'a> {
+    /// Start building an extension definition record for records of type `R`, laying the
+    /// extensions out one after another from just past the record's fixed fields.
+    ///
+    /// ```
+    /// # use igc::records::{BRecord, ExtensionDefRecord, IRecord};
+    /// let defs = ExtensionDefRecord::builder::<BRecord>()
+    ///     .add("FXA", 3)
+    ///     .and_then(|builder| builder.add("ENL", 3))
+    ///     .unwrap()
+    ///     .build();
+    /// assert_eq!(IRecord(defs).to_string(), "I023638FXA3941ENL");
+    /// ```
+    pub fn builder<R: Extendable>() -> ExtensionDefBuilder<'a> {
+        ExtensionDefBuilder {
+            next_byte: R::BASE_LENGTH + 1,
+            extensions: Vec::new(),
+        }
+    }
+
     /// Parse either kind of extension definition records (either I or J)
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         let first_byte = line.as_bytes()[0];
@@ -127,11 +259,11 @@ impl<'a> ExtensionDefRecord<'a> {
             return Err(ParseError::SyntaxError);
         }
 
-        let extensions = line[3..]
-            .as_bytes()
-            .chunks(Extension::STRING_LENGTH)
-            .map(unsafe { |buf| str::from_utf8_unchecked(buf) })
-            .map(Extension::parse)
+        let extensions = (0..num_extensions as usize)
+            .map(|i| {
+                let start = 3 + i * Extension::STRING_LENGTH;
+                Extension::parse(&line[start..start + Extension::STRING_LENGTH])
+            })
             .collect::<Result<_, _>>()?;
 
         Ok(Self {
@@ -140,6 +272,37 @@ impl<'a> ExtensionDefRecord<'a> {
         })
     }
 
+    /// Check that every extension's byte range is well formed and that no two ranges overlap,
+    /// returning `ParseError::BadExtension` if not.
+    ///
+    /// Gaps between ranges are allowed, as they are just unused bytes in the record.
+    ///
+    /// ```
+    /// # use igc::records::ExtensionDefRecord;
+    /// assert!(ExtensionDefRecord::parse("I023638FXA3941ENL").unwrap().validate().is_ok());
+    /// assert!(ExtensionDefRecord::parse("I023638FXA3741ENL").unwrap().validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), ParseError> {
+        let mut ranges = self
+            .extensions
+            .iter()
+            .map(|ext| ext.range)
+            .collect::<Vec<_>>();
+        if ranges.iter().any(|range| range.end_byte < range.start_byte) {
+            return Err(ParseError::BadExtension);
+        }
+
+        ranges.sort_by_key(|range| range.start_byte);
+        if ranges
+            .windows(2)
+            .any(|pair| pair[1].start_byte <= pair[0].end_byte)
+        {
+            return Err(ParseError::BadExtension);
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn fmt(&self, f: &mut fmt::Formatter, letter: char) -> fmt::Result {
         write!(f, "{}{:02}", letter, self.num_extensions)?;
         for ext in self.extensions.iter() {
@@ -149,6 +312,46 @@ impl<'a> ExtensionDefRecord<'a> {
     }
 }
 
+/// Builds an `ExtensionDefRecord` from a list of mnemonics and widths, working out each
+/// extension's byte range. Created with `ExtensionDefRecord::builder`.
+#[derive(Clone, Debug)]
+pub struct ExtensionDefBuilder<'a> {
+    next_byte: usize,
+    extensions: Vec<Extension<'a>>,
+}
+
+impl<'a> ExtensionDefBuilder<'a> {
+    /// Add an extension `width` bytes wide, directly after the previously added extension.
+    ///
+    /// Returns `ParseError::BadExtension` if the mnemonic isn't exactly three ASCII characters,
+    /// if the width is zero, or if the extension would end past byte 99, which can't be written
+    /// in an I or J record.
+    pub fn add(mut self, mnemonic: &'a str, width: u8) -> Result<Self, ParseError> {
+        if mnemonic.len() != 3 || !mnemonic.is_ascii() || width == 0 {
+            return Err(ParseError::BadExtension);
+        }
+
+        let start_byte = self.next_byte;
+        let end_byte = start_byte + width as usize - 1;
+        if end_byte > 99 {
+            return Err(ParseError::BadExtension);
+        }
+
+        self.extensions
+            .push(Extension::new(mnemonic, start_byte as u8, end_byte as u8));
+        self.next_byte = end_byte + 1;
+        Ok(self)
+    }
+
+    /// Finish building, setting `num_extensions` to the number of extensions added.
+    pub fn build(self) -> ExtensionDefRecord<'a> {
+        ExtensionDefRecord {
+            num_extensions: self.extensions.len() as u8,
+            extensions: self.extensions,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,18 +365,24 @@ mod tests {
             extensions: vec![
                 Extension {
                     mnemonic: "FXA",
-                    start_byte: 36,
-                    end_byte: 38,
+                    range: ExtensionRange {
+                        start_byte: 36,
+                        end_byte: 38,
+                    },
                 },
                 Extension {
                     mnemonic: "ENL",
-                    start_byte: 39,
-                    end_byte: 41,
+                    range: ExtensionRange {
+                        start_byte: 39,
+                        end_byte: 41,
+                    },
                 },
                 Extension {
                     mnemonic: "TAS",
-                    start_byte: 42,
-                    end_byte: 46,
+                    range: ExtensionRange {
+                        start_byte: 42,
+                        end_byte: 46,
+                    },
                 },
             ],
         };
@@ -181,6 +390,110 @@ mod tests {
         assert_eq!(parsed_record, expected);
     }
 
+    #[test]
+    fn extension_slice() {
+        let line = "B0941145152265N00032642WA0011500115012345";
+        let i_record = ExtensionDefRecord::parse("I023638FXA3941ENL").unwrap();
+
+        let fxa = &i_record.extensions[0];
+        let enl = &i_record.extensions[1];
+        assert_eq!(fxa.slice(line).unwrap(), "012");
+        assert_eq!(enl.slice(line).unwrap(), "345");
+
+        assert!(match enl.slice(&line[..40]) {
+            Err(ParseError::MissingExtension) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn extension_slice_matches_get_extension() {
+        use crate::records::BRecord;
+
+        let line = "B0941145152265N00032642WA0011500115012345";
+        let record = BRecord::parse(line).unwrap();
+        let fxa = Extension::new("FXA", 36, 38);
+        assert_eq!(
+            fxa.slice(line).unwrap(),
+            record.get_extension(fxa.range).unwrap()
+        );
+    }
+
+    #[test]
+    fn extension_slice_with_invalid_char_boundary() {
+        let ext = Extension::new("FOO", 3, 4);
+        assert!(ext.slice("AB\u{1107f}").is_err());
+    }
+
+    #[test]
+    fn validate_ranges() {
+        let clean = ExtensionDefRecord::parse("I033638FXA3941ENL4246TAS").unwrap();
+        assert!(clean.validate().is_ok());
+
+        let gaps = ExtensionDefRecord::parse("I023638FXA4246TAS").unwrap();
+        assert!(gaps.validate().is_ok());
+
+        let unordered = ExtensionDefRecord::parse("I024246TAS3638FXA").unwrap();
+        assert!(unordered.validate().is_ok());
+
+        let overlapping = ExtensionDefRecord::parse("I023638FXA3741ENL").unwrap();
+        assert!(match overlapping.validate() {
+            Err(ParseError::BadExtension) => true,
+            _ => false,
+        });
+
+        let backwards = ExtensionDefRecord {
+            num_extensions: 1,
+            extensions: vec![Extension {
+                mnemonic: "FXA",
+                range: ExtensionRange {
+                    start_byte: 38,
+                    end_byte: 36,
+                },
+            }],
+        };
+        assert!(backwards.validate().is_err());
+    }
+
+    #[test]
+    fn builder_matches_hand_written() {
+        use crate::records::{BRecord, IRecord, JRecord, KRecord};
+
+        let i_record = ExtensionDefRecord::builder::<BRecord>()
+            .add("FXA", 3)
+            .and_then(|builder| builder.add("ENL", 3))
+            .and_then(|builder| builder.add("TAS", 5))
+            .unwrap()
+            .build();
+        assert_eq!(
+            i_record,
+            ExtensionDefRecord::parse("I033638FXA3941ENL4246TAS").unwrap()
+        );
+        assert_eq!(IRecord(i_record).to_string(), "I033638FXA3941ENL4246TAS");
+
+        let j_record = ExtensionDefRecord::builder::<KRecord>()
+            .add("HDT", 5)
+            .unwrap()
+            .build();
+        assert_eq!(JRecord(j_record).to_string(), "J010812HDT");
+    }
+
+    #[test]
+    fn builder_rejects_bad_extensions() {
+        use crate::records::BRecord;
+
+        let builder = ExtensionDefRecord::builder::<BRecord>;
+        assert!(builder().add("FX", 3).is_err());
+        assert!(builder().add("FXAA", 3).is_err());
+        assert!(builder().add("FXA", 0).is_err());
+        assert_eq!(
+            builder().add("FXA", 1).unwrap().build().extensions,
+            vec![Extension::new("FXA", 36, 36)]
+        );
+        assert!(builder().add("FXA", 64).is_ok());
+        assert!(builder().add("FXA", 65).is_err());
+    }
+
     #[test]
     fn parse_with_invalid_char_boundary() {
         assert!(ExtensionDefRecord::parse("I\u{1107f}").is_err());
@@ -1,6 +1,6 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
 use crate::util::{ParseError, Time};
 
@@ -16,7 +16,10 @@ pub struct FRecord<'a> {
 impl<'a> FRecord<'a> {
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         if line.len() < 7 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 7,
+                got: line.len(),
+            });
         }
         if !line.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
@@ -24,11 +27,7 @@ impl<'a> FRecord<'a> {
 
         let time = line[1..7].parse()?;
 
-        let array_str = &line[7..];
-        if array_str.len() % 2 != 0 {
-            return Err(ParseError::SyntaxError);
-        }
-        let satellites = SatelliteArray::new(array_str);
+        let satellites = SatelliteArray::try_new(&line[7..])?;
 
         Ok(Self { time, satellites })
     }
@@ -47,17 +46,59 @@ pub struct SatelliteArray<'a> {
 }
 
 impl<'a> SatelliteArray<'a> {
+    /// Create an array from a string of two character satellite IDs.
+    ///
+    /// Panics if the string has an odd length; use `try_new` for input which isn't known to be
+    /// good.
     pub fn new(raw_str: &'a str) -> Self {
         assert!(raw_str.len() % 2 == 0);
         Self { raw_str }
     }
 
+    /// Create an array from a string of two character satellite IDs, returning an error if the
+    /// string has an odd length or contains non-ASCII characters.
+    pub fn try_new(raw_str: &'a str) -> Result<Self, ParseError> {
+        if raw_str.len() % 2 != 0 {
+            return Err(ParseError::SyntaxError);
+        }
+        if !raw_str.is_ascii() {
+            return Err(ParseError::NonASCIICharacters);
+        }
+
+        Ok(Self { raw_str })
+    }
+
+    pub(crate) fn as_str(&self) -> &'a str {
+        self.raw_str
+    }
+
+    /// The number of satellites in the array.
+    pub fn len(&self) -> usize {
+        self.raw_str.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.raw_str.is_empty()
+    }
+
     pub fn iter(&self) -> SatelliteArrayIter<'a> {
         SatelliteArrayIter {
             index: 0,
             raw_str: self.raw_str,
         }
     }
+
+    /// Iterate over the satellite IDs (PRN numbers) in the array, parsing each as a number.
+    ///
+    /// ```
+    /// # use igc::records::SatelliteArray;
+    /// let satellites = SatelliteArray::new("0412XX");
+    /// let ids = satellites.ids().map(Result::ok).collect::<Vec<_>>();
+    /// assert_eq!(ids, vec![Some(4), Some(12), None]);
+    /// ```
+    pub fn ids(&self) -> impl Iterator<Item = Result<u8, ParseError>> + 'a {
+        self.iter().map(|id| Ok(id.parse::<u8>()?))
+    }
 }
 
 pub struct SatelliteArrayIter<'a> {
@@ -121,6 +162,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn satellite_array_try_new() {
+        assert_eq!(
+            SatelliteArray::try_new("AABB").unwrap(),
+            SatelliteArray::new("AABB")
+        );
+        assert!(SatelliteArray::try_new("").is_ok());
+
+        assert!(match SatelliteArray::try_new("ABC") {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+        assert!(match SatelliteArray::try_new("A\u{e9}B") {
+            Err(ParseError::NonASCIICharacters) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn satellite_array_new_odd_length() {
+        SatelliteArray::new("ABC");
+    }
+
+    #[test]
+    fn satellite_count() {
+        let parsed_record = FRecord::parse("F095212AABBCC").unwrap();
+        assert_eq!(parsed_record.satellites.len(), 3);
+        assert!(!parsed_record.satellites.is_empty());
+        assert_eq!(parsed_record.satellites.ids().count(), 3);
+        assert!(parsed_record.satellites.ids().all(|id| id.is_err()));
+
+        assert_eq!(SatelliteArray::new("").len(), 0);
+        assert!(SatelliteArray::new("").is_empty());
+    }
+
+    #[test]
+    fn satellite_ids() {
+        let parsed_record = FRecord::parse("F095212010232").unwrap();
+        let ids = parsed_record
+            .satellites
+            .ids()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(ids, vec![1, 2, 32]);
+    }
+
     #[test]
     fn frecord_format() {
         let expected_string = "F095212AABBCCDDEE";
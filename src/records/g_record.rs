@@ -1,7 +1,9 @@
+use alloc::string::String;
+use core::{fmt, str};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
+use crate::records::Record;
 use crate::util::ParseError;
 
 /// A security record.
@@ -27,9 +29,240 @@ impl<'a> fmt::Display for GRecord<'a> {
     }
 }
 
+/// Joins the data of a multi-line security record into a single string.
+///
+/// Loggers commonly split a long signature over several consecutive G lines. This finds the first
+/// G record in `records` and concatenates its data with that of every G record immediately
+/// following it. Returns an empty string if there are no G records.
+pub fn collect_security_record(records: &[Record]) -> String {
+    let mut signature = String::new();
+    let g_records = records
+        .iter()
+        .skip_while(|rec| match rec {
+            Record::G(_) => false,
+            _ => true,
+        })
+        .take_while(|rec| match rec {
+            Record::G(_) => true,
+            _ => false,
+        });
+
+    for rec in g_records {
+        if let Record::G(g_rec) = rec {
+            signature.push_str(g_rec.data);
+        }
+    }
+
+    signature
+}
+
+/// Reasons a file's signature can fail verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecurityError {
+    /// The file has no G record.
+    MissingSecurityRecord,
+
+    /// A G record line isn't valid UTF-8.
+    MalformedSecurityRecord,
+
+    /// The validator rejected the signature.
+    InvalidSignature,
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            SecurityError::MissingSecurityRecord => "No G record found",
+            SecurityError::MalformedSecurityRecord => "Malformed G record",
+            SecurityError::InvalidSignature => "Invalid signature",
+        };
+
+        write!(f, "{}", message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SecurityError {}
+
+/// Checks the signature in a file's security record.
+///
+/// Signature schemes are vendor specific, so this crate only provides the bookkeeping (see
+/// `verify_signature`); implementations of this trait provide the cryptography.
+pub trait SecurityValidator {
+    /// Verify that `g_record` is a valid signature of `signed_bytes`.
+    fn verify(
+        &self,
+        signed_bytes: &[u8],
+        g_record: &GRecord,
+    ) -> Result<(), SecurityError>;
+}
+
+/// A validator which accepts every signature, for testing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopValidator;
+
+impl SecurityValidator for NoopValidator {
+    fn verify(&self, _: &[u8], _: &GRecord) -> Result<(), SecurityError> {
+        Ok(())
+    }
+}
+
+/// Verify the signature of a file, given its raw contents.
+///
+/// The validator is passed exactly the bytes covered by the security record: everything before
+/// the first line beginning with `G`, including the line ending of the line before it. The G
+/// record it is passed has the data of that line joined with that of every G line immediately
+/// following it, as with `collect_security_record`, and without any line ending.
+///
+/// ```
+/// # use igc::records::{verify_signature, GRecord, SecurityError, SecurityValidator};
+/// struct Checker;
+/// impl SecurityValidator for Checker {
+///     fn verify(&self, signed: &[u8], g_record: &GRecord) -> Result<(), SecurityError> {
+///         assert_eq!(signed, b"AXXXABC\r\nLFoo\r\n");
+///         assert_eq!(g_record.data, "ABCDEF");
+///         Ok(())
+///     }
+/// }
+///
+/// let raw = b"AXXXABC\r\nLFoo\r\nGABC\r\nGDEF\r\nLTrailing\r\n";
+/// assert_eq!(verify_signature(raw, &Checker), Ok(()));
+/// ```
+pub fn verify_signature<V: SecurityValidator>(
+    raw: &[u8],
+    validator: &V,
+) -> Result<(), SecurityError> {
+    let mut signature_start = None;
+    let mut signature = String::new();
+
+    // Lines end in any of CRLF, LF or a lone CR, as with `split_lines`
+    let mut offset = 0;
+    while offset < raw.len() {
+        let line_start = offset;
+        let rest = &raw[offset..];
+        let (line, ending_len) = match rest.iter().position(|&b| b == b'\r' || b == b'\n')
+        {
+            Some(end) if rest[end..].starts_with(b"\r\n") => (&rest[..end], 2),
+            Some(end) => (&rest[..end], 1),
+            None => (rest, 0),
+        };
+        offset += line.len() + ending_len;
+
+        if line.first() != Some(&b'G') {
+            if signature_start.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        signature_start.get_or_insert(line_start);
+        let line =
+            str::from_utf8(line).map_err(|_| SecurityError::MalformedSecurityRecord)?;
+        signature.push_str(&line[1..]);
+    }
+
+    let signature_start = signature_start.ok_or(SecurityError::MissingSecurityRecord)?;
+    let g_record = GRecord { data: &signature };
+    validator.verify(&raw[..signature_start], &g_record)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::records::LRecord;
+
+    #[test]
+    fn grecord_parse() {
+        let parsed =
+            GRecord::parse("GREJNGJERJKNJKRE31895478537H43982FJN9248F942389T433T")
+                .unwrap();
+        let expected = GRecord {
+            data: "REJNGJERJKNJKRE31895478537H43982FJN9248F942389T433T",
+        };
+
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn collect_multi_line_security_record() {
+        let records = vec![
+            Record::L(LRecord { log_string: "Foo" }),
+            Record::G(GRecord { data: "ABC" }),
+            Record::G(GRecord { data: "DEF" }),
+            Record::G(GRecord { data: "GHI" }),
+            Record::L(LRecord { log_string: "Bar" }),
+            Record::G(GRecord { data: "JKL" }),
+        ];
+
+        assert_eq!(collect_security_record(&records), "ABCDEFGHI");
+        assert_eq!(collect_security_record(&records[..1]), "");
+    }
+
+    struct RecordingValidator {
+        signed: core::cell::RefCell<Vec<u8>>,
+        signature: core::cell::RefCell<String>,
+        accept: bool,
+    }
+
+    impl RecordingValidator {
+        fn new(accept: bool) -> Self {
+            RecordingValidator {
+                signed: Default::default(),
+                signature: Default::default(),
+                accept,
+            }
+        }
+    }
+
+    impl SecurityValidator for RecordingValidator {
+        fn verify(&self, signed: &[u8], g_record: &GRecord) -> Result<(), SecurityError> {
+            *self.signed.borrow_mut() = signed.to_vec();
+            *self.signature.borrow_mut() = g_record.data.to_string();
+            if self.accept {
+                Ok(())
+            } else {
+                Err(SecurityError::InvalidSignature)
+            }
+        }
+    }
+
+    #[test]
+    fn verify_signature_span() {
+        let raw =
+            b"AXXXABC\nB0941145152265N00032642WA0011500115\nGABC\r\nGDEF\nLAfter\nGXYZ\n";
+        let validator = RecordingValidator::new(true);
+
+        assert_eq!(verify_signature(raw, &validator), Ok(()));
+        assert_eq!(
+            &validator.signed.borrow()[..],
+            &b"AXXXABC\nB0941145152265N00032642WA0011500115\n"[..]
+        );
+        assert_eq!(*validator.signature.borrow(), "ABCDEF");
+
+        let raw = b"AXXXABC\rLFoo\rGABC\rGDEF\rLAfter\r";
+        let validator = RecordingValidator::new(true);
+
+        assert_eq!(verify_signature(raw, &validator), Ok(()));
+        assert_eq!(&validator.signed.borrow()[..], &b"AXXXABC\rLFoo\r"[..]);
+        assert_eq!(*validator.signature.borrow(), "ABCDEF");
+    }
+
+    #[test]
+    fn verify_signature_errors() {
+        assert_eq!(
+            verify_signature(b"AXXXABC\nLFoo\n", &NoopValidator),
+            Err(SecurityError::MissingSecurityRecord)
+        );
+        assert_eq!(
+            verify_signature(b"AXXXABC\nG\xff\n", &NoopValidator),
+            Err(SecurityError::MalformedSecurityRecord)
+        );
+        assert_eq!(
+            verify_signature(b"GABC", &RecordingValidator::new(false)),
+            Err(SecurityError::InvalidSignature)
+        );
+        assert_eq!(verify_signature(b"GABC", &NoopValidator), Ok(()));
+    }
 
     proptest! {
         #[test]
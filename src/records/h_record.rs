@@ -1,8 +1,10 @@
+use alloc::string::{String, ToString};
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-use crate::util::ParseError;
+use crate::records::HasFreeText;
+use crate::util::{Date, ParseError};
 
 /// Enumeration of the different sources an H record can come from.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -54,7 +56,10 @@ impl<'a> HRecord<'a> {
         assert_eq!(bytes[0], b'H');
 
         if bytes.len() < 6 {
-            return Err(ParseError::SyntaxError);
+            return Err(ParseError::TooShort {
+                expected: 6,
+                got: bytes.len(),
+            });
         }
         if !line.bytes().take(5).all(|b| b.is_ascii()) {
             return Err(ParseError::NonASCIICharacters);
@@ -81,6 +86,192 @@ impl<'a> HRecord<'a> {
             data,
         })
     }
+
+    /// Copy this record into one which owns its strings, so it can outlive the line it was
+    /// parsed from.
+    pub fn into_owned(self) -> HRecordBuf {
+        HRecordBuf {
+            data_source: self.data_source,
+            mnemonic: self.mnemonic.to_string(),
+            friendly_name: self.friendly_name.map(str::to_string),
+            data: self.data.to_string(),
+        }
+    }
+
+    /// The data of this header with any trailing whitespace removed.
+    ///
+    /// Some loggers pad header data with spaces to a fixed width. The padding is kept in `data`,
+    /// and written back out by `Display`, so that a file can be reproduced byte for byte.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// let record = HRecord::parse("HFGTYGLIDERTYPE:ASW 20   ").unwrap();
+    /// assert_eq!(record.data, "ASW 20   ");
+    /// assert_eq!(record.data_trimmed(), "ASW 20");
+    /// ```
+    pub fn data_trimmed(&self) -> &'a str {
+        self.data.trim_end()
+    }
+
+    /// The data of this header as an integer, if it is nothing but digits once any surrounding
+    /// whitespace is trimmed.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// assert_eq!(HRecord::parse("HFFXA035").unwrap().as_u32(), Some(35));
+    /// assert_eq!(HRecord::parse("HFGIDGLIDERID:D-KOOL").unwrap().as_u32(), None);
+    /// ```
+    pub fn as_u32(&self) -> Option<u32> {
+        let data = self.data.trim();
+        if data.is_empty() || !data.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        data.parse().ok()
+    }
+
+    /// The data of this header as a decimal number, if it is nothing but an optional sign,
+    /// digits and a decimal point once any surrounding whitespace is trimmed.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// assert_eq!(HRecord::parse("HFMASMASS:512.5").unwrap().as_f64(), Some(512.5));
+    /// assert_eq!(HRecord::parse("HFMASMASS:heavy").unwrap().as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        let data = self.data.trim();
+        let digits = data.trim_start_matches(|c| c == '+' || c == '-');
+        if data.len() - digits.len() > 1
+            || !digits.bytes().any(|b| b.is_ascii_digit())
+            || !digits.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        {
+            return None;
+        }
+        data.parse().ok()
+    }
+
+    /// The date of the flight, if this is a DTE header.
+    ///
+    /// Both the older `HFDTEDDMMYY` form and the newer `HFDTEDATE:DDMMYY,NN` form, which adds the
+    /// number of the flight on that day, are recognised.
+    ///
+    /// ```
+    /// # use igc::{records::HRecord, util::Date};
+    /// let old = HRecord::parse("HFDTE230718").unwrap();
+    /// let new = HRecord::parse("HFDTEDATE:230718,02").unwrap();
+    /// assert_eq!(old.as_flight_date(), Some(Date::from_dmy(23, 7, 18)));
+    /// assert_eq!(new.as_flight_date(), Some(Date::from_dmy(23, 7, 18)));
+    /// ```
+    pub fn as_flight_date(&self) -> Option<Date> {
+        let (date, _) = self.date_fields()?;
+        Date::parse(date).ok()
+    }
+
+    /// The number of the flight on its date, if this is a DTE header in the newer form which
+    /// includes it.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// let old = HRecord::parse("HFDTE230718").unwrap();
+    /// let new = HRecord::parse("HFDTEDATE:230718,02").unwrap();
+    /// assert_eq!(old.flight_of_day(), None);
+    /// assert_eq!(new.flight_of_day(), Some(2));
+    /// ```
+    pub fn flight_of_day(&self) -> Option<u8> {
+        let (_, counter) = self.date_fields()?;
+        let counter = counter?;
+        if counter.is_empty() || !counter.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        counter.parse().ok()
+    }
+
+    /// The offset of local time from UTC in hours, if this is a TZN or TZO header.
+    ///
+    /// Times in an IGC file are always UTC, so this is only useful for displaying local times.
+    /// Returns `None` for any other header, or if the offset can't be parsed or is further than
+    /// 14 hours from UTC.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// let record = HRecord::parse("HFTZNTIMEZONE:+2").unwrap();
+    /// assert_eq!(record.utc_offset_hours(), Some(2.));
+    /// ```
+    pub fn utc_offset_hours(&self) -> Option<f32> {
+        if self.mnemonic != "TZN" && self.mnemonic != "TZO" {
+            return None;
+        }
+
+        let offset = self.as_f64()? as f32;
+        if offset.abs() <= 14. {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// Split the data of a DTE header into the date and the optional flight of the day counter.
+    fn date_fields(&self) -> Option<(&'a str, Option<&'a str>)> {
+        if self.mnemonic != "DTE" {
+            return None;
+        }
+
+        let data = self.data;
+        Some(match data.find(',') {
+            Some(comma_idx) => {
+                (data[..comma_idx].trim(), Some(data[comma_idx + 1..].trim()))
+            }
+            None => (data.trim(), None),
+        })
+    }
+
+    /// The byte index at which `data` begins within the line this record was parsed from.
+    ///
+    /// ```
+    /// # use igc::records::HRecord;
+    /// let line = "HFGIDGLIDERID:D-KOOL";
+    /// let record = HRecord::parse(line).unwrap();
+    /// assert_eq!(&line[record.data_offset()..], "D-KOOL");
+    /// ```
+    pub fn data_offset(&self) -> usize {
+        match self.friendly_name {
+            Some(friendly_name) => 5 + friendly_name.len() + 1,
+            None => 5,
+        }
+    }
+}
+
+/// An owned version of `HRecord`, which doesn't borrow from the line it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct HRecordBuf {
+    pub data_source: DataSource,
+    pub mnemonic: String,
+    pub friendly_name: Option<String>,
+    pub data: String,
+}
+
+impl HRecordBuf {
+    /// Borrow this as an `HRecord`, for use with the rest of the API.
+    pub fn as_record(&self) -> HRecord<'_> {
+        HRecord {
+            data_source: self.data_source.clone(),
+            mnemonic: &self.mnemonic,
+            friendly_name: self.friendly_name.as_ref().map(String::as_str),
+            data: &self.data,
+        }
+    }
+}
+
+impl<'a> From<HRecord<'a>> for HRecordBuf {
+    fn from(record: HRecord<'a>) -> Self {
+        record.into_owned()
+    }
+}
+
+impl<'a> HasFreeText for HRecord<'a> {
+    fn free_text(&self) -> Option<&str> {
+        Some(self.data)
+    }
 }
 
 impl<'a> fmt::Display for HRecord<'a> {
@@ -110,6 +301,44 @@ impl<'a> fmt::Display for HRecord<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn numeric_headers() {
+        let fxa = HRecord::parse("HFFXA035").unwrap();
+        assert_eq!(fxa.mnemonic, "FXA");
+        assert_eq!(fxa.as_u32(), Some(35));
+        assert_eq!(fxa.as_f64(), Some(35.));
+
+        let text = HRecord::parse("HFGTYGLIDERTYPE:ASW 20").unwrap();
+        assert_eq!(text.as_u32(), None);
+        assert_eq!(text.as_f64(), None);
+
+        for data in &["", "-5", "1.5", "12a", "99999999999"] {
+            let line = format!("HFFXAACCURACY:{}", data);
+            assert_eq!(HRecord::parse(&line).unwrap().as_u32(), None, "{:?}", data);
+        }
+        for &(data, expected) in
+            &[(" 12 ", Some(12.)), ("-0.5", Some(-0.5)), ("+3", Some(3.))]
+        {
+            let line = format!("HFMASMASS:{}", data);
+            assert_eq!(HRecord::parse(&line).unwrap().as_f64(), expected);
+        }
+        for data in &["inf", "NaN", "1e5", "--1", ".", "1.2.3"] {
+            let line = format!("HFMASMASS:{}", data);
+            assert_eq!(HRecord::parse(&line).unwrap().as_f64(), None, "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn trailing_whitespace() {
+        let line = "HFGTYGLIDERTYPE:ASW 20   ";
+        let record = HRecord::parse(line).unwrap();
+
+        assert_eq!(record.data, "ASW 20   ");
+        assert_eq!(record.data_trimmed(), "ASW 20");
+        assert_eq!(record.free_text_trimmed(), Some("ASW 20"));
+        assert_eq!(record.to_string(), line);
+    }
+
     #[test]
     fn hrecord_parse() {
         let sample_string = "HFGIDGLIDERID:D-KOOL";
@@ -153,6 +382,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hrecord_data_offset() {
+        for line in &[
+            "HFGIDGLIDERID:D-KOOL",
+            "HFDTE230718",
+            "HAaA :a",
+            "HFDTEDATE:",
+        ] {
+            let record = HRecord::parse(line).unwrap();
+            assert_eq!(&line[record.data_offset()..], record.data);
+        }
+    }
+
+    #[test]
+    fn into_owned_round_trip() {
+        let record = HRecord::parse("HFGIDGLIDERID:D-KOOL").unwrap();
+        let owned = HRecordBuf::from(record.clone());
+
+        assert_eq!(owned.friendly_name, Some("GLIDERID".to_string()));
+        assert_eq!(owned.as_record(), record);
+    }
+
+    #[test]
+    fn flight_date() {
+        let date = Some(Date::from_dmy(23, 7, 18));
+        for line in &[
+            "HFDTE230718",
+            "HFDTE 230718",
+            "HFDTEDATE:230718",
+            "HFDTEDATE:230718,02",
+            "HODTEDATE: 230718, 2",
+        ] {
+            assert_eq!(
+                HRecord::parse(line).unwrap().as_flight_date(),
+                date,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn flight_date_invalid() {
+        for line in &[
+            "HFDTE ",
+            "HFDTE2307",
+            "HFDTEDATE:,02",
+            "HFDTE320718",
+            "HFDTEDATE:2307180,1",
+            "HFGIDGLIDERID:230718",
+        ] {
+            assert_eq!(
+                HRecord::parse(line).unwrap().as_flight_date(),
+                None,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn flight_of_day() {
+        let cases = [
+            ("HFDTEDATE:230718,02", Some(2)),
+            ("HFDTEDATE:230718, 13", Some(13)),
+            ("HFDTEDATE:230718,1", Some(1)),
+            ("HFDTEDATE:230718", None),
+            ("HFDTE230718", None),
+            ("HFDTEDATE:230718,", None),
+            ("HFDTEDATE:230718,+1", None),
+            ("HFDTEDATE:230718,999", None),
+            ("HFGIDGLIDERID:230718,02", None),
+        ];
+        for &(line, expected) in &cases {
+            assert_eq!(
+                HRecord::parse(line).unwrap().flight_of_day(),
+                expected,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn utc_offset_hours() {
+        let cases = [
+            ("HFTZNTIMEZONE:2", Some(2.)),
+            ("HFTZNTIMEZONE:1.0", Some(1.)),
+            ("HFTZNTIMEZONE: +2 ", Some(2.)),
+            ("HPTZOTIMEZONE:-5.5", Some(-5.5)),
+            ("HFTZN-3", Some(-3.)),
+            ("HFTZNTIMEZONE:", None),
+            ("HFTZNTIMEZONE:UTC", None),
+            ("HFTZNTIMEZONE:inf", None),
+            ("HFTZNTIMEZONE:+-2", None),
+            ("HFTZNTIMEZONE:15", None),
+            ("HFGIDGLIDERID:2", None),
+        ];
+        for &(line, expected) in &cases {
+            assert_eq!(
+                HRecord::parse(line).unwrap().utc_offset_hours(),
+                expected,
+                "{}",
+                line
+            );
+        }
+    }
+
     #[test]
     fn parse_with_invalid_char_boundary() {
         assert!(HRecord::parse("H\u{1107f}").is_err());
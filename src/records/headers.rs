@@ -0,0 +1,136 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{iter::FromIterator, slice};
+
+use crate::records::{HRecord, Record};
+
+/// The H records of a file, which can be looked up by mnemonic.
+///
+/// Some recorders write the same header more than once. When that happens the last occurrence
+/// wins: `header` returns the last H record with the given mnemonic, while `headers_iter` still
+/// yields every H record in its original order, so the file can be written back out faithfully.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Headers<'a> {
+    records: Vec<HRecord<'a>>,
+    by_mnemonic: BTreeMap<&'a str, usize>,
+}
+
+impl<'a> Headers<'a> {
+    /// Collect the H records out of a parsed file.
+    pub fn from_records(records: &[Record<'a>]) -> Self {
+        records
+            .iter()
+            .filter_map(|record| match record {
+                Record::H(rec) => Some(rec.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the last H record with the given three letter mnemonic, eg. `"GID"`.
+    ///
+    /// ```
+    /// # use igc::records::{Headers, Record, RecordReader};
+    /// let text = "HFGIDGLIDERID:D-KOOL\nHFGIDGLIDERID:D-KOOK\n";
+    /// let records = RecordReader::new(text).collect::<Result<Vec<_>, _>>().unwrap();
+    /// let headers = Headers::from_records(&records);
+    /// assert_eq!(headers.header("GID").unwrap().data, "D-KOOK");
+    /// ```
+    pub fn header(&self, mnemonic: &str) -> Option<&HRecord<'a>> {
+        self.by_mnemonic
+            .get(mnemonic)
+            .map(|&index| &self.records[index])
+    }
+
+    /// The name of the pilot in charge, from the PLT header.
+    pub fn pilot_in_charge(&self) -> Option<&'a str> {
+        self.data("PLT")
+    }
+
+    /// The name of the second crew member, from the CM2 header.
+    pub fn crew(&self) -> Option<&'a str> {
+        self.data("CM2")
+    }
+
+    /// The glider's registration, from the GID header.
+    pub fn glider_registration(&self) -> Option<&'a str> {
+        self.data("GID")
+    }
+
+    /// The glider's competition ID, from the CID header.
+    pub fn competition_id(&self) -> Option<&'a str> {
+        self.data("CID")
+    }
+
+    /// The glider's type, from the GTY header.
+    pub fn glider_type(&self) -> Option<&'a str> {
+        self.data("GTY")
+    }
+
+    fn data(&self, mnemonic: &str) -> Option<&'a str> {
+        self.header(mnemonic).map(|header| header.data)
+    }
+
+    /// Iterate over every H record, including any duplicates, in the order they appeared.
+    pub fn headers_iter(&self) -> slice::Iter<'_, HRecord<'a>> {
+        self.records.iter()
+    }
+}
+
+impl<'a> FromIterator<HRecord<'a>> for Headers<'a> {
+    fn from_iter<I: IntoIterator<Item = HRecord<'a>>>(iter: I) -> Self {
+        let records = iter.into_iter().collect::<Vec<_>>();
+        let by_mnemonic = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| (record.mnemonic, index))
+            .collect();
+
+        Headers {
+            records,
+            by_mnemonic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::parse_records;
+
+    #[test]
+    fn last_occurrence_wins() {
+        let records = parse_records(
+            "HFGIDGLIDERID:D-KOOL\n\
+             HFDTE230718\n\
+             B0941145152265N00032642WA0011500115\n\
+             HFGIDGLIDERID:D-KOOK\n",
+        );
+        let headers = Headers::from_records(&records);
+
+        assert_eq!(headers.header("GID").unwrap().data, "D-KOOK");
+        assert_eq!(headers.header("DTE").unwrap().data, "230718");
+        assert_eq!(headers.header("PLT"), None);
+
+        let data = headers.headers_iter().map(|h| h.data).collect::<Vec<_>>();
+        assert_eq!(data, vec!["D-KOOL", "230718", "D-KOOK"]);
+    }
+
+    #[test]
+    fn example_accessors() {
+        let records = parse_records(include_str!("../../examples/example.igc"));
+        let headers = Headers::from_records(&records);
+
+        assert_eq!(headers.pilot_in_charge(), Some("Steve Lynn"));
+        assert_eq!(headers.crew(), Some("Robin May"));
+        assert_eq!(headers.glider_registration(), Some("D-KOOL"));
+        assert_eq!(headers.competition_id(), Some("13"));
+        assert_eq!(headers.glider_type(), Some("ASH 25E"));
+    }
+
+    #[test]
+    fn missing_accessors() {
+        let headers = Headers::from_records(&parse_records("HFDTE230718\n"));
+        assert_eq!(headers.pilot_in_charge(), None);
+        assert_eq!(headers.glider_registration(), None);
+    }
+}
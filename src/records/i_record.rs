@@ -1,8 +1,10 @@
+use alloc::vec::Vec;
+use core::{fmt, ops};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, str};
 
-use crate::records::extension::ExtensionDefRecord;
+use crate::records::extension::{Extension, ExtensionDefRecord};
+use crate::records::{BRecord, Record};
 use crate::util::ParseError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -19,16 +21,70 @@ impl<'a> IRecord<'a> {
     }
 }
 
+impl<'a> ops::Deref for IRecord<'a> {
+    type Target = ExtensionDefRecord<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl<'a> fmt::Display for IRecord<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f, 'I')
     }
 }
 
+/// The layout of the extensions in a file's B records, as defined by its I record.
+///
+/// A file should only have one I record. Should there be more than one, the last wins, as
+/// that's the layout in force for the final fixes of the flight. Note that this differs from
+/// `RecordReader::capture_extension_defs`, which sees records one at a time and keeps the first.
+///
+/// ```
+/// # use igc::records::{FixExtensions, Record, RecordReader};
+/// let file = "I023638FXA3941ENL\nB0941145152265N00032642WA0011500115012345\n";
+/// let records = RecordReader::new(file).collect::<Result<Vec<_>, _>>().unwrap();
+/// let extensions = FixExtensions::from_records(&records);
+/// if let Record::B(fix) = &records[1] {
+///     assert_eq!(extensions.fix_extension_value(fix, "ENL"), Some("345"));
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixExtensions<'a> {
+    pub fix_extensions: Vec<Extension<'a>>,
+}
+
+impl<'a> FixExtensions<'a> {
+    /// Find the extension definitions from the last I record in a parsed file. If there is no I
+    /// record, no extensions are defined.
+    pub fn from_records(records: &[Record<'a>]) -> Self {
+        let fix_extensions = records
+            .iter()
+            .rev()
+            .find_map(|record| match record {
+                Record::I(rec) => Some(rec.extensions.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        FixExtensions { fix_extensions }
+    }
+
+    /// Get the value of the extension with the given mnemonic from a fix, if it is both defined
+    /// and present.
+    pub fn fix_extension_value<'r>(
+        &self,
+        fix: &'r BRecord,
+        mnemonic: &str,
+    ) -> Option<&'r str> {
+        fix.extensions(&self.fix_extensions).get(mnemonic)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::records::extension::Extension;
 
     #[test]
     fn irecord_format() {
@@ -44,4 +100,72 @@ mod tests {
 
         assert_eq!(format!("{}", record), expected_string);
     }
+
+    #[test]
+    fn irecord_round_trip() {
+        for line in &["I00", "I013638FXA", "I033638FXA3941ENL4246TAS"] {
+            let record = Record::parse_line(line).unwrap();
+            assert_eq!(record.to_string(), *line);
+        }
+    }
+
+    #[test]
+    fn irecord_parse() {
+        let record = IRecord::parse("I023638FXA3941ENL").unwrap();
+        assert_eq!(record.num_extensions, 2);
+        assert_eq!(record.extensions[1], Extension::new("ENL", 39, 41));
+    }
+
+    #[test]
+    fn irecord_parse_non_ascii() {
+        assert!(IRecord::parse("I013638FXÄ").is_err());
+    }
+
+    #[test]
+    fn irecord_parse_multibyte_char_at_length() {
+        // Each is the correct length of 10 bytes, with a multi-byte character straddling a
+        // boundary within the extension definition
+        for line in &["I013638F\u{e9}", "I013638\u{e9}A", "I01363\u{1107f}"] {
+            assert!(match IRecord::parse(line) {
+                Err(ParseError::NonASCIICharacters) => true,
+                _ => false,
+            });
+        }
+    }
+
+    #[test]
+    fn fix_extensions_last_wins() {
+        let lines = [
+            "I013638FXA",
+            "B0941145152265N00032642WA0011500115012345",
+            "I023638FXA3941ENL",
+            "B0941155152265N00032642WA0011500115012345",
+        ];
+        let records = lines
+            .iter()
+            .map(|line| Record::parse_line(line).unwrap())
+            .collect::<Vec<_>>();
+        let fix = match &records[3] {
+            Record::B(fix) => fix,
+            _ => unreachable!(),
+        };
+
+        let extensions = FixExtensions::from_records(&records);
+        assert_eq!(extensions.fix_extensions.len(), 2);
+        assert_eq!(extensions.fix_extension_value(fix, "FXA"), Some("012"));
+        assert_eq!(extensions.fix_extension_value(fix, "ENL"), Some("345"));
+        assert_eq!(extensions.fix_extension_value(fix, "TAS"), None);
+
+        let extensions = FixExtensions::from_records(&records[1..2]);
+        assert!(extensions.fix_extensions.is_empty());
+        assert_eq!(extensions.fix_extension_value(fix, "FXA"), None);
+    }
+
+    proptest! {
+        #[test]
+        #[allow(unused_must_use)]
+        fn parse_doesnt_crash(s in "I\\PC*") {
+            IRecord::parse(&s);
+        }
+    }
 }
@@ -1,6 +1,6 @@
+use core::{fmt, ops};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, str};
 
 use crate::records::extension::ExtensionDefRecord;
 use crate::util::ParseError;
@@ -19,6 +19,14 @@ impl<'a> JRecord<'a> {
     }
 }
 
+impl<'a> ops::Deref for JRecord<'a> {
+    type Target = ExtensionDefRecord<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl<'a> fmt::Display for JRecord<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f, 'J')
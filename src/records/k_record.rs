@@ -1,8 +1,8 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-use crate::records::extension::Extendable;
+use crate::records::extension::{Extendable, Extension, ExtensionView};
 use crate::util::{ParseError, Time};
 
 /// An extension data record.
@@ -19,8 +19,11 @@ impl<'a> KRecord<'a> {
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         assert_eq!(line.as_bytes()[0], b'K');
 
-        if line.len() <= 7 {
-            return Err(ParseError::SyntaxError);
+        if line.len() < 8 {
+            return Err(ParseError::TooShort {
+                expected: 8,
+                got: line.len(),
+            });
         }
         if !line.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
@@ -34,6 +37,56 @@ impl<'a> KRecord<'a> {
             extension_string,
         })
     }
+
+    /// Get a view of this record's extensions, as laid out by the extensions defined in the
+    /// file's J record.
+    ///
+    /// ```
+    /// # use igc::records::{JRecord, KRecord};
+    /// let j_record = JRecord::parse("J020810HDT1113WDI").unwrap();
+    /// let record = KRecord::parse("K095214090270").unwrap();
+    /// let extensions = record.extensions(&j_record.extensions);
+    /// assert_eq!(extensions.get("WDI"), Some("270"));
+    /// ```
+    pub fn extensions<'r, 'd>(
+        &'r self,
+        defs: &'d [Extension<'d>],
+    ) -> ExtensionView<'r, 'd, Self> {
+        ExtensionView::new(self, defs)
+    }
+
+    /// The true heading in degrees, from the HDT extension.
+    ///
+    /// Returns `None` if HDT isn't defined in `defs`, or isn't present in this record as a
+    /// number.
+    ///
+    /// ```
+    /// # use igc::records::{JRecord, KRecord};
+    /// let j_record = JRecord::parse("J030810HDT1113WDI1416WSP").unwrap();
+    /// let record = KRecord::parse("K095214090270025").unwrap();
+    /// assert_eq!(record.heading(&j_record.extensions), Some(90));
+    /// assert_eq!(record.wind_direction(&j_record.extensions), Some(270));
+    /// assert_eq!(record.wind_speed(&j_record.extensions), Some(25));
+    /// ```
+    pub fn heading(&self, defs: &[Extension]) -> Option<u16> {
+        self.extensions(defs).get("HDT")?.parse().ok()
+    }
+
+    /// The direction the wind is blowing from in degrees, from the WDI extension.
+    ///
+    /// Returns `None` if WDI isn't defined in `defs`, or isn't present in this record as a
+    /// number.
+    pub fn wind_direction(&self, defs: &[Extension]) -> Option<u16> {
+        self.extensions(defs).get("WDI")?.parse().ok()
+    }
+
+    /// The wind speed in km/h, from the WSP extension.
+    ///
+    /// Returns `None` if WSP isn't defined in `defs`, or isn't present in this record as a
+    /// number.
+    pub fn wind_speed(&self, defs: &[Extension]) -> Option<u16> {
+        self.extensions(defs).get("WSP")?.parse().ok()
+    }
 }
 
 impl<'a> Extendable for KRecord<'a> {
@@ -53,7 +106,7 @@ impl<'a> fmt::Display for KRecord<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::records::extension::Extension;
+    use crate::records::extension::{Extension, ExtensionRange};
 
     #[test]
     fn krecord_parse() {
@@ -90,24 +143,57 @@ mod tests {
             extension_string: "FooTheBar",
         };
         let ext1 = Extension {
-            start_byte: 8,
-            end_byte: 10,
+            range: ExtensionRange {
+                start_byte: 8,
+                end_byte: 10,
+            },
             mnemonic: "One",
         };
         let ext2 = Extension {
-            start_byte: 11,
-            end_byte: 13,
+            range: ExtensionRange {
+                start_byte: 11,
+                end_byte: 13,
+            },
             mnemonic: "Two",
         };
         let ext3 = Extension {
-            start_byte: 14,
-            end_byte: 16,
+            range: ExtensionRange {
+                start_byte: 14,
+                end_byte: 16,
+            },
             mnemonic: "Th3",
         };
 
-        assert_eq!(record.get_extension(&ext1).unwrap(), "Foo");
-        assert_eq!(record.get_extension(&ext2).unwrap(), "The");
-        assert_eq!(record.get_extension(&ext3).unwrap(), "Bar");
+        assert_eq!(record.get_extension(ext1.range).unwrap(), "Foo");
+        assert_eq!(record.get_extension(ext2.range).unwrap(), "The");
+        assert_eq!(record.get_extension(ext3.range).unwrap(), "Bar");
+    }
+
+    #[test]
+    fn krecord_extensions_from_j_record() {
+        use crate::records::JRecord;
+
+        let j_record = JRecord::parse("J030810HDT1113WDI1416WSP").unwrap();
+        let defs = &j_record.extensions;
+        let record = KRecord::parse("K095214359180012").unwrap();
+
+        assert_eq!(
+            record.extensions(defs).iter().collect::<Vec<_>>(),
+            vec![("HDT", "359"), ("WDI", "180"), ("WSP", "012")]
+        );
+        assert_eq!(record.heading(defs), Some(359));
+        assert_eq!(record.wind_direction(defs), Some(180));
+        assert_eq!(record.wind_speed(defs), Some(12));
+
+        // Too short to hold the wind extensions
+        let record = KRecord::parse("K095214359").unwrap();
+        assert_eq!(record.heading(defs), Some(359));
+        assert_eq!(record.wind_direction(defs), None);
+        assert_eq!(record.wind_speed(defs), None);
+        assert_eq!(record.heading(&[]), None);
+
+        let record = KRecord::parse("K095214ABC180012").unwrap();
+        assert_eq!(record.heading(defs), None);
     }
 
     proptest! {
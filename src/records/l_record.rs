@@ -1,7 +1,8 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
+use crate::records::HasFreeText;
 use crate::util::ParseError;
 
 /// A simple plaintext log, used by some manufacturers for propietary extensions.
@@ -11,6 +12,22 @@ pub struct LRecord<'a> {
     pub log_string: &'a str,
 }
 
+/// The observation zone of a task point, as written by SeeYou in an `LSEEYOU OZ=` record.
+///
+/// Radii are in meters and angles in degrees. Any field missing from the record is zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct ObservationZone {
+    /// The index of the task point, where -1 is the start.
+    pub index: i32,
+    pub radius1_m: f64,
+    pub radius2_m: f64,
+    pub angle1: f64,
+    pub angle2: f64,
+    /// Whether the zone is a line rather than a sector.
+    pub line: bool,
+}
+
 impl<'a> LRecord<'a> {
     pub fn parse(line: &'a str) -> Result<Self, ParseError> {
         assert_eq!(line.as_bytes()[0], b'L');
@@ -19,6 +36,76 @@ impl<'a> LRecord<'a> {
 
         Ok(Self { log_string })
     }
+
+    /// Parse this record as a SeeYou observation zone.
+    ///
+    /// Returns `None` if this isn't an `LSEEYOU OZ=` record, or if any of the fields it uses
+    /// can't be parsed. Fields other than the radii, angles and line flag are ignored.
+    ///
+    /// ```
+    /// # use igc::records::LRecord;
+    /// let record = LRecord::parse("LSEEYOU OZ=0,Style=1,R1=500m,A1=180").unwrap();
+    /// let zone = record.as_seeyou_oz().unwrap();
+    /// assert_eq!(zone.index, 0);
+    /// assert_eq!(zone.radius1_m, 500.);
+    /// assert_eq!(zone.angle1, 180.);
+    /// ```
+    pub fn as_seeyou_oz(&self) -> Option<ObservationZone> {
+        const PREFIX: &str = "SEEYOU OZ=";
+        if !self.log_string.starts_with(PREFIX) {
+            return None;
+        }
+
+        let mut fields = self.log_string[PREFIX.len()..].split(',');
+        let mut zone = ObservationZone {
+            index: fields.next()?.trim().parse().ok()?,
+            radius1_m: 0.,
+            radius2_m: 0.,
+            angle1: 0.,
+            angle2: 0.,
+            line: false,
+        };
+
+        for field in fields {
+            let mut parts = field.splitn(2, '=');
+            let key = parts.next()?.trim();
+            let value = match parts.next() {
+                Some(value) => value.trim(),
+                None => continue,
+            };
+
+            match key {
+                "R1" => zone.radius1_m = parse_radius(value)?,
+                "R2" => zone.radius2_m = parse_radius(value)?,
+                "A1" => zone.angle1 = value.parse().ok()?,
+                "A2" => zone.angle2 = value.parse().ok()?,
+                "Line" => zone.line = value == "1",
+                _ => {}
+            }
+        }
+
+        Some(zone)
+    }
+}
+
+/// Parse a SeeYou radius such as `500m` or `0.5km` into meters.
+fn parse_radius(value: &str) -> Option<f64> {
+    if value.ends_with("km") {
+        value[..value.len() - 2]
+            .parse::<f64>()
+            .ok()
+            .map(|km| km * 1000.)
+    } else if value.ends_with('m') {
+        value[..value.len() - 1].parse().ok()
+    } else {
+        value.parse().ok()
+    }
+}
+
+impl<'a> HasFreeText for LRecord<'a> {
+    fn free_text(&self) -> Option<&str> {
+        Some(self.log_string)
+    }
 }
 
 impl<'a> fmt::Display for LRecord<'a> {
@@ -52,11 +139,76 @@ mod tests {
         assert_eq!(format!("{}", record), expected_string);
     }
 
+    #[test]
+    fn lrecord_round_trip_trailing_spaces() {
+        let sample_string = "LFoo the bar   ";
+        let parsed = LRecord::parse(sample_string).unwrap();
+        assert_eq!(parsed.log_string, "Foo the bar   ");
+        assert_eq!(format!("{}", parsed), sample_string);
+    }
+
+    #[test]
+    fn seeyou_oz() {
+        let record =
+            LRecord::parse("LSEEYOU OZ=-1,Style=2,SpeedStyle=0,R1=5000m,A1=180,Line=1")
+                .unwrap();
+        assert_eq!(
+            record.as_seeyou_oz(),
+            Some(ObservationZone {
+                index: -1,
+                radius1_m: 5000.,
+                radius2_m: 0.,
+                angle1: 180.,
+                angle2: 0.,
+                line: true,
+            })
+        );
+
+        let record =
+            LRecord::parse("LSEEYOU OZ=1,Style=1,R1=0.5km,A1=45,R2=500m,A2=22.5,A12=90")
+                .unwrap();
+        assert_eq!(
+            record.as_seeyou_oz(),
+            Some(ObservationZone {
+                index: 1,
+                radius1_m: 500.,
+                radius2_m: 500.,
+                angle1: 45.,
+                angle2: 22.5,
+                line: false,
+            })
+        );
+    }
+
+    #[test]
+    fn not_seeyou_oz() {
+        for line in &[
+            "LFoo the bar",
+            "LSEEYOU TSK,NoStart=10:00:00",
+            "LSEEYOU OZ=",
+            "LSEEYOU OZ=1,R1=far",
+            "LSEEYOU OZ=x,R1=500m",
+        ] {
+            assert_eq!(
+                LRecord::parse(line).unwrap().as_seeyou_oz(),
+                None,
+                "{}",
+                line
+            );
+        }
+    }
+
     proptest! {
         #[test]
         #[allow(unused_must_use)]
         fn parse_doesnt_crash(s in "L\\PC*") {
             LRecord::parse(&s);
         }
+
+        #[test]
+        fn round_trip(s in "L\\PC*") {
+            let parsed = LRecord::parse(&s).unwrap();
+            prop_assert_eq!(format!("{}", parsed), s);
+        }
     }
 }
@@ -1,5 +1,10 @@
 //! Low level record parsing API
 //!
+//! Every record type borrows its text from the line it was parsed from. The fixes and headers,
+//! which are what most code keeps hold of, have owned counterparts in `BRecordBuf` and
+//! `HRecordBuf`. Any other record can be kept beyond the life of its line as an `OwnedRecord`,
+//! with `Record::into_owned`.
+//!
 //! ```
 //! # extern crate igc;
 //! use igc::records::{DataSource, Record};
@@ -14,9 +19,9 @@
 //! }
 //! ```
 
+use core::{convert::TryFrom, fmt, str};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
 use crate::util::ParseError;
 
@@ -29,24 +34,44 @@ mod extension;
 mod f_record;
 mod g_record;
 mod h_record;
+mod headers;
 mod i_record;
 mod j_record;
 mod k_record;
 mod l_record;
+mod owned;
+mod reader;
+mod split;
+mod writer;
 
 pub use self::a_record::*;
-pub use self::b_record::{BRecord, FixValid};
-pub use self::c_record::{CRecordDeclaration, CRecordTurnpoint};
+pub use self::b_record::{AltitudeSource, BRecord, BRecordBuf, FixValid};
+pub use self::c_record::{CRecordDeclaration, CRecordTurnpoint, Task, TaskDeclaration};
 pub use self::d_record::DRecord;
-pub use self::e_record::ERecord;
-pub use self::extension::{Extendable, Extension};
-pub use self::f_record::FRecord;
-pub use self::g_record::GRecord;
-pub use self::h_record::{DataSource, HRecord};
-pub use self::i_record::IRecord;
+pub use self::e_record::{ERecord, EventKind};
+pub use self::extension::{
+    Extendable, Extension, ExtensionDefBuilder, ExtensionDefRecord, ExtensionRange,
+    ExtensionView, ExtensionViewIter,
+};
+pub use self::f_record::{FRecord, SatelliteArray, SatelliteArrayIter};
+pub use self::g_record::{
+    collect_security_record, verify_signature, GRecord, NoopValidator, SecurityError,
+    SecurityValidator,
+};
+pub use self::h_record::{DataSource, HRecord, HRecordBuf};
+pub use self::headers::Headers;
+pub use self::i_record::{FixExtensions, IRecord};
 pub use self::j_record::JRecord;
 pub use self::k_record::KRecord;
-pub use self::l_record::LRecord;
+pub use self::l_record::{LRecord, ObservationZone};
+pub use self::owned::OwnedRecord;
+pub use self::reader::{
+    fixes, split_lines, try_fixes, ByteIndices, RecordReader, SplitLines,
+};
+pub use self::split::{split_flights, SplitOn};
+#[cfg(feature = "std")]
+pub use self::writer::write_records;
+pub use self::writer::{file_order, to_igc_string};
 
 /// Sum type of all possible records in an IGC file.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -71,12 +96,97 @@ pub enum Record<'a> {
     L(LRecord<'a>),
 
     /// Wildcard record type, containing the string that wasn't recognized.
+    #[cfg_attr(feature = "serde", serde(with = "unrecognised_line"))]
     Unrecognised(&'a str),
 }
 
+/// Internally tagged enums can't hold a bare string, so an unrecognised line is (de)serialized
+/// as a struct containing it.
+#[cfg(feature = "serde")]
+mod unrecognised_line {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize, Serialize)]
+    struct Unrecognised<'a> {
+        line: &'a str,
+    }
+
+    pub fn serialize<S: Serializer>(
+        line: &&str,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Unrecognised { line }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<&'de str, D::Error> {
+        Unrecognised::deserialize(deserializer).map(|unrecognised| unrecognised.line)
+    }
+}
+
+/// Options controlling how strictly `Record::parse_line_with_options` parses a line.
+///
+/// The default is strict, following the specification to the letter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Permit non-ASCII characters in free text fields, such as header data and task, turnpoint
+    /// and pilot names, as often seen in European files. Structural fields such as times,
+    /// coordinates and extensions must always be ASCII.
+    pub allow_latin1_text: bool,
+}
+
+/// A line parsed by `Record::parse_line_with`, which may be a custom record type recognised by
+/// the caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsedLine<'a, C> {
+    Record(Record<'a>),
+    Custom(C),
+}
+
+/// The type of a record, as given by its first letter.
+///
+/// Both flavours of C record share the same kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecordKind {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    Unrecognised,
+}
+
+/// Implemented by records which can carry human readable free text, such as a header's data or
+/// a task's name.
+pub trait HasFreeText {
+    /// The free text of this record, if it has any.
+    fn free_text(&self) -> Option<&str>;
+
+    /// The free text of this record with any trailing whitespace, such as padding to a fixed
+    /// width, removed.
+    fn free_text_trimmed(&self) -> Option<&str> {
+        self.free_text().map(str::trim_end)
+    }
+}
+
 impl<'a> Record<'a> {
     /// Perform a minimal parsing of a single IGC file line.
     ///
+    /// The record type is picked from the first byte of the line alone, with no backtracking:
+    /// each of the letters A to L goes straight to that record's parser, and any error it
+    /// returns is returned from here. C records are the one exception, being told apart by their
+    /// ninth byte, which is the N or S of a turnpoint's latitude but a digit of a declaration's
+    /// time. A line starting with any other byte is `Record::Unrecognised`, and an empty line is
+    /// a `SyntaxError`.
+    ///
     /// ```
     /// use igc::records::{DataSource, Record};
     /// match Record::parse_line("HFFTYFRTYPE:LXNAV,LX8000F") {
@@ -101,7 +211,10 @@ impl<'a> Record<'a> {
                 // In a turnpoint C record, the 9th character is the N/S of the latitutde
                 // In a declaration type C record, it is a number (part of the declaration time)
                 if line.len() < 9 {
-                    return Err(ParseError::SyntaxError);
+                    return Err(ParseError::TooShort {
+                        expected: 18,
+                        got: line.len(),
+                    });
                 }
 
                 match line.as_bytes()[8] {
@@ -123,6 +236,300 @@ impl<'a> Record<'a> {
 
         Ok(rec)
     }
+
+    /// Parse a single IGC file line as with `parse_line`, but give `custom` the chance to parse
+    /// any line which would otherwise be `Record::Unrecognised`.
+    ///
+    /// This allows proprietary record types to be handled without changing the dispatcher.
+    /// The line is first dispatched exactly as by `parse_line`, and `custom` is only consulted
+    /// once that has found the line to be unrecognised. So lines starting with a letter this
+    /// crate understands are never passed to `custom`, even if they fail to parse, and an empty
+    /// line is still a `SyntaxError`. If `custom` returns `None` the line stays
+    /// `Record::Unrecognised`.
+    ///
+    /// ```
+    /// use igc::records::{ParsedLine, Record};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct MRecord<'a>(&'a str);
+    ///
+    /// fn parse_m(line: &str) -> Option<MRecord<'_>> {
+    ///     if line.starts_with('M') {
+    ///         Some(MRecord(&line[1..]))
+    ///     } else {
+    ///         None
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Record::parse_line_with("Mvendor data", parse_m).unwrap(),
+    ///     ParsedLine::Custom(MRecord("vendor data"))
+    /// );
+    /// assert_eq!(
+    ///     Record::parse_line_with("Nother", parse_m).unwrap(),
+    ///     ParsedLine::Record(Record::Unrecognised("Nother"))
+    /// );
+    /// ```
+    pub fn parse_line_with<C, F>(
+        line: &'a str,
+        custom: F,
+    ) -> Result<ParsedLine<'a, C>, ParseError>
+    where
+        F: FnOnce(&'a str) -> Option<C>,
+    {
+        let record = Self::parse_line(line)?;
+        Ok(match record {
+            Record::Unrecognised(line) => match custom(line) {
+                Some(custom_record) => ParsedLine::Custom(custom_record),
+                None => ParsedLine::Record(record),
+            },
+            record => ParsedLine::Record(record),
+        })
+    }
+
+    /// Parse a single IGC file line as with `parse_line`, with the given strictness.
+    ///
+    /// `parse_line` itself is lenient, accepting non-ASCII characters in free text fields (and
+    /// rejecting them everywhere else). Unless `options.allow_latin1_text` is set, this instead
+    /// rejects any line which isn't entirely ASCII with `ParseError::NonASCIICharacters`, as the
+    /// specification requires.
+    ///
+    /// Files actually encoded as Latin-1 need decoding first, eg. with `util::decode_igc_bytes`.
+    ///
+    /// ```
+    /// use igc::records::{ParseOptions, Record};
+    /// let line = "C5156040N00038120WZ\u{fc}rich";
+    /// assert!(Record::parse_line_with_options(line, &ParseOptions::default()).is_err());
+    ///
+    /// let relaxed = ParseOptions { allow_latin1_text: true };
+    /// assert!(Record::parse_line_with_options(line, &relaxed).is_ok());
+    /// ```
+    pub fn parse_line_with_options(
+        line: &'a str,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        if !options.allow_latin1_text && !line.is_ascii() {
+            return Err(ParseError::NonASCIICharacters);
+        }
+
+        Self::parse_line(line)
+    }
+
+    /// Parse a line of an IGC file directly from its bytes, without the caller first having to
+    /// validate the whole file as UTF-8.
+    ///
+    /// The line is parsed as with `parse_line` once it has been checked to be valid UTF-8,
+    /// which any line of plain ASCII as the specification requires will be. Otherwise
+    /// `ParseError::Utf8Error` is returned; files in other encodings should be decoded with
+    /// `util::decode_igc_bytes` first.
+    ///
+    /// ```
+    /// use igc::records::Record;
+    /// let record = Record::parse_line_bytes(b"LFoo the bar").unwrap();
+    /// assert_eq!(record.to_string(), "LFoo the bar");
+    /// assert!(Record::parse_line_bytes(b"LFoo\xe9").is_err());
+    /// ```
+    pub fn parse_line_bytes(line: &'a [u8]) -> Result<Self, ParseError> {
+        Self::parse_line(str::from_utf8(line)?)
+    }
+
+    /// The kind of this record.
+    ///
+    /// ```
+    /// use igc::records::{Record, RecordKind};
+    /// let record = Record::parse_line("B0941145152265N00032642WA0011500115").unwrap();
+    /// assert_eq!(record.kind(), RecordKind::B);
+    /// ```
+    pub fn kind(&self) -> RecordKind {
+        match self {
+            Record::A(_) => RecordKind::A,
+            Record::B(_) => RecordKind::B,
+            Record::CDeclaration(_) | Record::CTurnpoint(_) => RecordKind::C,
+            Record::D(_) => RecordKind::D,
+            Record::E(_) => RecordKind::E,
+            Record::F(_) => RecordKind::F,
+            Record::G(_) => RecordKind::G,
+            Record::H(_) => RecordKind::H,
+            Record::I(_) => RecordKind::I,
+            Record::J(_) => RecordKind::J,
+            Record::K(_) => RecordKind::K,
+            Record::L(_) => RecordKind::L,
+            Record::Unrecognised(_) => RecordKind::Unrecognised,
+        }
+    }
+
+    /// The letter this record starts with when written out.
+    ///
+    /// For an unrecognised record this is the first character of the line, if it has one.
+    pub fn record_letter(&self) -> Option<char> {
+        match self {
+            Record::A(_) => Some('A'),
+            Record::B(_) => Some('B'),
+            Record::CDeclaration(_) | Record::CTurnpoint(_) => Some('C'),
+            Record::D(_) => Some('D'),
+            Record::E(_) => Some('E'),
+            Record::F(_) => Some('F'),
+            Record::G(_) => Some('G'),
+            Record::H(_) => Some('H'),
+            Record::I(_) => Some('I'),
+            Record::J(_) => Some('J'),
+            Record::K(_) => Some('K'),
+            Record::L(_) => Some('L'),
+            Record::Unrecognised(line) => line.chars().next(),
+        }
+    }
+
+    /// Borrow the inner `BRecord`, if this is one. The same as `as_b`.
+    ///
+    /// ```
+    /// use igc::records::Record;
+    /// let record = Record::parse_line("B0941145152265N00032642WA0011500115").unwrap();
+    /// assert_eq!(record.as_fix().unwrap().gps_alt, 115);
+    /// assert!(record.as_header().is_none());
+    /// ```
+    pub fn as_fix(&self) -> Option<&BRecord<'a>> {
+        self.as_b()
+    }
+
+    /// Parse the line starting at byte offset `start` of `input`, without first slicing it out.
+    ///
+    /// Returns the record along with the number of bytes consumed, including the line's
+    /// terminator if it has one, so that `start + consumed` is the offset of the next line.
+    ///
+    /// ```
+    /// use igc::records::Record;
+    /// let input = "0001 HFDTE140718\r\n0002 HFFTYFRTYPE:LXNAV,LX8000F\r\n";
+    /// let (record, consumed) = Record::parse_at(input, 5).unwrap();
+    /// assert_eq!(consumed, 13);
+    /// assert_eq!(record.to_string(), "HFDTE140718");
+    /// ```
+    pub fn parse_at(input: &'a str, start: usize) -> Result<(Self, usize), ParseError> {
+        let (line, consumed) = line_at(input, start)?;
+        Ok((Self::parse_line(line)?, consumed))
+    }
+}
+
+/// Get the line beginning at byte offset `start` of `input`, without its terminator, along with
+/// the number of bytes up to and including the terminator.
+pub(crate) fn line_at(input: &str, start: usize) -> Result<(&str, usize), ParseError> {
+    if start > input.len() || !input.is_char_boundary(start) {
+        return Err(ParseError::SyntaxError);
+    }
+
+    let rest = &input[start..];
+    let (line, consumed) = match rest.find('\n') {
+        Some(newline_idx) => (&rest[..newline_idx], newline_idx + 1),
+        None => (rest, rest.len()),
+    };
+
+    let line = if line.ends_with('\r') {
+        &line[..line.len() - 1]
+    } else {
+        line
+    };
+
+    Ok((line, consumed))
+}
+
+impl<'a> TryFrom<&'a str> for Record<'a> {
+    type Error = ParseError;
+
+    fn try_from(line: &'a str) -> Result<Self, ParseError> {
+        Self::parse_line(line)
+    }
+}
+
+/// Implement `TryFrom<&str>` for record types by delegating to their `parse` method.
+///
+/// The individual parsers assume they are given a line starting with the right letter, so that
+/// is checked first, giving a `SyntaxError` rather than a panic.
+macro_rules! impl_try_from_str {
+    ($($record:ident => $letter:expr,)*) => {
+        $(
+            impl<'a> TryFrom<&'a str> for $record<'a> {
+                type Error = ParseError;
+
+                fn try_from(line: &'a str) -> Result<Self, ParseError> {
+                    if line.as_bytes().first() != Some(&$letter) {
+                        return Err(ParseError::SyntaxError);
+                    }
+                    Self::parse(line)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_str! {
+    ARecord => b'A',
+    BRecord => b'B',
+    CRecordDeclaration => b'C',
+    CRecordTurnpoint => b'C',
+    DRecord => b'D',
+    ERecord => b'E',
+    FRecord => b'F',
+    GRecord => b'G',
+    HRecord => b'H',
+    IRecord => b'I',
+    JRecord => b'J',
+    KRecord => b'K',
+    LRecord => b'L',
+}
+
+/// Add `as_` and `into_` accessors to `Record` for each of its variants, returning the inner
+/// record if it is that variant.
+macro_rules! impl_variant_accessors {
+    ($($variant:ident($record:ident) => $as_fn:ident, $into_fn:ident;)*) => {
+        impl<'a> Record<'a> {
+            $(
+                /// Borrow the inner record, if this is the right variant.
+                pub fn $as_fn(&self) -> Option<&$record<'a>> {
+                    match self {
+                        Record::$variant(rec) => Some(rec),
+                        _ => None,
+                    }
+                }
+
+                /// Take the inner record, if this is the right variant.
+                pub fn $into_fn(self) -> Option<$record<'a>> {
+                    match self {
+                        Record::$variant(rec) => Some(rec),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+    };
+}
+
+impl_variant_accessors! {
+    A(ARecord) => as_a, into_a;
+    B(BRecord) => as_b, into_b;
+    CDeclaration(CRecordDeclaration) => as_declaration, into_declaration;
+    CTurnpoint(CRecordTurnpoint) => as_turnpoint, into_turnpoint;
+    D(DRecord) => as_d, into_d;
+    E(ERecord) => as_e, into_e;
+    F(FRecord) => as_f, into_f;
+    G(GRecord) => as_g, into_g;
+    H(HRecord) => as_header, into_header;
+    I(IRecord) => as_i, into_i;
+    J(JRecord) => as_j, into_j;
+    K(KRecord) => as_k, into_k;
+    L(LRecord) => as_l, into_l;
+}
+
+/// Records with free text pass it through. Every other kind of record has none.
+impl<'a> HasFreeText for Record<'a> {
+    fn free_text(&self) -> Option<&str> {
+        match self {
+            Record::CDeclaration(rec) => rec.free_text(),
+            Record::CTurnpoint(rec) => rec.free_text(),
+            Record::E(rec) => rec.free_text(),
+            Record::H(rec) => rec.free_text(),
+            Record::L(rec) => rec.free_text(),
+            _ => None,
+        }
+    }
 }
 
 impl<'a> fmt::Display for Record<'a> {
@@ -153,6 +560,198 @@ mod tests {
     use super::*;
     use crate::util::Manufacturer;
 
+    #[test]
+    fn parse_line_bytes() {
+        for line in &[
+            "B0941145152265N00032642WA0011500115",
+            "HFPLTPILOT:Zo\u{eb}",
+            "XFoo",
+        ] {
+            assert_eq!(
+                Record::parse_line_bytes(line.as_bytes()).unwrap(),
+                Record::parse_line(line).unwrap()
+            );
+        }
+
+        assert!(match Record::parse_line_bytes(b"HFPLTPILOT:Zo\xeb") {
+            Err(ParseError::Utf8Error(_)) => true,
+            _ => false,
+        });
+        assert!(Record::parse_line_bytes(b"").is_err());
+    }
+
+    #[test]
+    fn parse_line_with_custom() {
+        let parse_m = |line: &str| {
+            assert!(!line.starts_with('L'));
+            if line.starts_with('M') {
+                Some(line.len())
+            } else {
+                None
+            }
+        };
+
+        assert_eq!(
+            Record::parse_line_with("MFoo", parse_m).unwrap(),
+            ParsedLine::Custom(4)
+        );
+        assert_eq!(
+            Record::parse_line_with("LFoo", parse_m).unwrap(),
+            ParsedLine::Record(Record::parse_line("LFoo").unwrap())
+        );
+        assert_eq!(
+            Record::parse_line_with("XFoo", parse_m).unwrap(),
+            ParsedLine::Record(Record::Unrecognised("XFoo"))
+        );
+        assert!(Record::parse_line_with("", parse_m).is_err());
+    }
+
+    #[test]
+    fn records_are_clone_and_eq() {
+        use crate::util::{Compass, RawCoord, RawLatitude, RawLongitude, RawPosition};
+
+        fn assert_clone_eq<T: Clone + Eq>() {}
+
+        assert_clone_eq::<Record>();
+        assert_clone_eq::<ARecord>();
+        assert_clone_eq::<BRecord>();
+        assert_clone_eq::<CRecordDeclaration>();
+        assert_clone_eq::<CRecordTurnpoint>();
+        assert_clone_eq::<DRecord>();
+        assert_clone_eq::<ERecord>();
+        assert_clone_eq::<FRecord>();
+        assert_clone_eq::<GRecord>();
+        assert_clone_eq::<HRecord>();
+        assert_clone_eq::<IRecord>();
+        assert_clone_eq::<JRecord>();
+        assert_clone_eq::<KRecord>();
+        assert_clone_eq::<LRecord>();
+        assert_clone_eq::<RawCoord>();
+        assert_clone_eq::<RawLatitude>();
+        assert_clone_eq::<RawLongitude>();
+        assert_clone_eq::<RawPosition>();
+        assert_clone_eq::<Compass>();
+    }
+
+    #[test]
+    fn try_into_records() {
+        use core::convert::TryInto;
+
+        let line = "B0941145152265N00032642WA0011500115";
+        let fix: BRecord = line.try_into().unwrap();
+        assert_eq!(fix, BRecord::parse(line).unwrap());
+
+        let record: Record = line.try_into().unwrap();
+        assert_eq!(record, Record::B(fix));
+
+        // The wrong record type is an error rather than a panic
+        assert!(match BRecord::try_from("LFoo") {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+        assert!(LRecord::try_from("").is_err());
+    }
+
+    #[test]
+    fn parse_line_with_options() {
+        let strict = ParseOptions::default();
+        let relaxed = ParseOptions {
+            allow_latin1_text: true,
+        };
+
+        let line = "C5156040N00038120WLBZ-L\u{e9}ighton Buzzard NE";
+        assert!(match Record::parse_line_with_options(line, &strict) {
+            Err(ParseError::NonASCIICharacters) => true,
+            _ => false,
+        });
+        match Record::parse_line_with_options(line, &relaxed).unwrap() {
+            Record::CTurnpoint(turnpoint) => assert_eq!(
+                turnpoint.turnpoint_name,
+                Some("LBZ-L\u{e9}ighton Buzzard NE")
+            ),
+            other => panic!("unexpected record {:?}", other),
+        }
+
+        // Structural fields must be ASCII whatever the options
+        let line = "B0941145152265N00032642WA0011500115\u{e9}";
+        assert!(Record::parse_line_with_options(line, &relaxed).is_err());
+
+        let line = "B0941145152265N00032642WA0011500115";
+        assert!(Record::parse_line_with_options(line, &strict).is_ok());
+    }
+
+    #[test]
+    fn collect_free_text() {
+        let text = "AXXXABC\n\
+                    HFPLTPILOTINCHARGE:Steve Lynn\n\
+                    C230718092044000000000201Foo task\n\
+                    C5111359N00101899WSTART\n\
+                    C5110179N00102644W\n\
+                    B0941145152265N00032642WA0011500115\n\
+                    E094114PEVPilot event\n\
+                    LCAMFoo the bar\n";
+        let free_text = RecordReader::new(text)
+            .map(Result::unwrap)
+            .filter_map(|record| record.free_text().map(str::to_string))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            free_text,
+            vec![
+                "Steve Lynn",
+                "Foo task",
+                "START",
+                "Pilot event",
+                "CAMFoo the bar"
+            ]
+        );
+    }
+
+    #[test]
+    fn free_text_trimmed() {
+        let record = Record::parse_line("LCAMFoo the bar  ").unwrap();
+        assert_eq!(record.free_text(), Some("CAMFoo the bar  "));
+        assert_eq!(record.free_text_trimmed(), Some("CAMFoo the bar"));
+        assert_eq!(record.to_string(), "LCAMFoo the bar  ");
+
+        let record = Record::parse_line("B0941145152265N00032642WA0011500115").unwrap();
+        assert_eq!(record.free_text_trimmed(), None);
+    }
+
+    #[test]
+    fn variant_accessors() {
+        let fix = Record::parse_line("B0941145152265N00032642WA0011500115").unwrap();
+        assert_eq!(fix.as_b().unwrap().pressure_alt, 115);
+        assert_eq!(fix.as_fix(), fix.as_b());
+        assert!(fix.as_header().is_none());
+        assert!(fix.as_turnpoint().is_none());
+
+        let header = Record::parse_line("HFDTE230718").unwrap();
+        assert_eq!(header.as_header().unwrap().mnemonic, "DTE");
+        assert!(header.as_fix().is_none());
+
+        let turnpoint = Record::parse_line("C5111359N00101899WSTART").unwrap();
+        assert_eq!(
+            turnpoint.as_turnpoint().unwrap().turnpoint_name,
+            Some("START")
+        );
+        assert!(turnpoint.as_declaration().is_none());
+
+        let records = RecordReader::new(include_str!("../../examples/example.igc"))
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        let fix_count = records
+            .iter()
+            .filter(|record| record.kind() == RecordKind::B)
+            .count();
+        let fixes = records
+            .into_iter()
+            .filter_map(Record::into_b)
+            .collect::<Vec<_>>();
+        assert_eq!(fixes.len(), fix_count);
+        assert!(fix_count > 0);
+    }
+
     #[test]
     fn parse_empty_string() {
         assert!(Record::parse_line("").is_err());
@@ -188,11 +787,93 @@ mod tests {
         assert_eq!(format!("{}", rec), expected_str);
     }
 
+    #[test]
+    fn kind_matches_letter() {
+        let lines = [
+            "ACAMWatFoo",
+            "B0941145152265N00032642WA0011500115",
+            "C230718092044000000000204Foo task",
+            "C5156040N00038120WLBZ-Leighton Buzzard NE",
+            "D1ABCD",
+            "E160245PEVFoo bar",
+            "F095212AABBCCDDEE",
+            "GREJNGJERJKNJKRE31895478537H43982FJN9248F942389T433T",
+            "HFFTYFRTYPE:LXNAV,LX8000F",
+            "I023638FXA3941ENL",
+            "J010812HDT",
+            "K095214FooTheBar",
+            "LFoo the bar",
+            "XFoo",
+        ];
+
+        for line in lines.iter() {
+            let record = Record::parse_line(line).unwrap();
+            assert_eq!(record.record_letter(), line.chars().next());
+        }
+
+        assert_eq!(Record::parse_line(lines[2]).unwrap().kind(), RecordKind::C);
+        assert_eq!(Record::parse_line(lines[3]).unwrap().kind(), RecordKind::C);
+        assert_eq!(
+            Record::parse_line("XFoo").unwrap().kind(),
+            RecordKind::Unrecognised
+        );
+        assert_eq!(Record::Unrecognised("").record_letter(), None);
+    }
+
+    #[test]
+    fn parse_at_offsets() {
+        let input = "12 ACAMWatFoo\n13 LXXXbar";
+        let (rec, consumed) = Record::parse_at(input, 3).unwrap();
+        assert_eq!(consumed, 11);
+        assert_eq!(rec.to_string(), "ACAMWatFoo");
+
+        let (rec, consumed) = Record::parse_at(input, 17).unwrap();
+        assert_eq!(consumed, 7);
+        assert_eq!(rec.to_string(), "LXXXbar");
+
+        assert!(Record::parse_at(input, input.len()).is_err());
+        assert!(Record::parse_at(input, input.len() + 1).is_err());
+        assert!(Record::parse_at("🌀", 1).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip_every_variant() {
+        let lines = [
+            "ACAMWatFoo",
+            "B0941145152265N00032642WA0011500115",
+            "C230718092044000000000204Foo task",
+            "C5156040N00038120WLBZ-Leighton Buzzard NE",
+            "D1ABCD",
+            "E160245PEVFoo bar",
+            "F095212AABBCCDDEE",
+            "GREJNGJERJKNJKRE31895478537H43982FJN9248F942389T433T",
+            "HFFTYFRTYPE:LXNAV,LX8000F",
+            "I023638FXA3941ENL",
+            "J010812HDT",
+            "K095214FooTheBar",
+            "LFoo the bar",
+            "XFoo",
+        ];
+
+        for line in lines.iter() {
+            let record = Record::parse_line(line).unwrap();
+            let json = serde_json::to_string(&record).unwrap();
+            assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+        }
+    }
+
     proptest! {
         #[test]
         #[allow(unused_must_use)]
         fn doesnt_crash(s in "\\PC*") {
             Record::parse_line(&s);
         }
+
+        #[test]
+        #[allow(unused_must_use)]
+        fn bytes_doesnt_crash(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..64)) {
+            Record::parse_line_bytes(&bytes);
+        }
     }
 }
@@ -0,0 +1,107 @@
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
+use crate::records::Record;
+use crate::util::ParseError;
+
+/// A record which owns the line it was parsed from, so can outlive the buffer it came from.
+///
+/// The borrowed `Record` is kept as the fast path: an `OwnedRecord` holds on to a copy of the
+/// line, and re-parses it whenever the record itself is needed.
+///
+/// ```
+/// use igc::records::{OwnedRecord, Record};
+/// let records = vec![
+///     "HFDTE140718".parse::<OwnedRecord>().unwrap(),
+///     "LFoo the bar".parse::<OwnedRecord>().unwrap(),
+/// ];
+/// match records[1].record().unwrap() {
+///     Record::L(rec) => assert_eq!(rec.log_string, "Foo the bar"),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedRecord {
+    line: String,
+}
+
+impl OwnedRecord {
+    /// The record this line parses to.
+    ///
+    /// A record created with `FromStr` has already been parsed once, so this only fails for
+    /// one created with `Record::into_owned` whose written out form doesn't parse back.
+    pub fn record(&self) -> Result<Record<'_>, ParseError> {
+        Record::parse_line(&self.line)
+    }
+
+    /// The line this record holds.
+    ///
+    /// With `FromStr` this is the line that was parsed. `Record::into_owned` has no line to
+    /// keep, so the record is written out as by its `Display` impl, which may not match the
+    /// line it was originally parsed from.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    pub fn into_line(self) -> String {
+        self.line
+    }
+}
+
+impl FromStr for OwnedRecord {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, ParseError> {
+        Record::parse_line(line)?;
+        Ok(OwnedRecord {
+            line: line.to_string(),
+        })
+    }
+}
+
+impl<'a> Record<'a> {
+    /// Copy this record into one which doesn't borrow from the line it was parsed from.
+    ///
+    /// The record is written out again to do so. When the line itself is still to hand, parsing
+    /// it as an `OwnedRecord` keeps it exactly as it was.
+    pub fn into_owned(self) -> OwnedRecord {
+        OwnedRecord {
+            line: self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for OwnedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_owned() {
+        let line = String::from("B0941145152265N00032642WA0011500115");
+        let owned = line.parse::<OwnedRecord>().unwrap();
+        drop(line);
+
+        assert_eq!(
+            owned.record().unwrap(),
+            Record::parse_line("B0941145152265N00032642WA0011500115").unwrap()
+        );
+        assert_eq!(owned.to_string(), "B0941145152265N00032642WA0011500115");
+        assert!("B123".parse::<OwnedRecord>().is_err());
+    }
+
+    #[test]
+    fn into_owned() {
+        let owned = {
+            let line = String::from("LFoo the bar");
+            Record::parse_line(&line).unwrap().into_owned()
+        };
+        assert_eq!(owned.line(), "LFoo the bar");
+        assert_eq!(owned.into_line(), "LFoo the bar");
+    }
+}
@@ -0,0 +1,395 @@
+use crate::records::{BRecord, FixValid, IRecord, JRecord, Record};
+use crate::util::{ParseError, ParseErrorAt};
+
+/// Streams the records out of the text of a whole IGC file, one line at a time.
+///
+/// Lines may end in any mix of `\r\n`, `\n` or a lone `\r`, as split by `split_lines`.
+///
+/// Optionally, the reader can remember the first I and J records it comes across. As these appear
+/// before any B or K records in a conformant file, the extensions of every subsequent fix can then
+/// be decoded without the caller having to track them.
+///
+/// ```
+/// # use igc::records::{Record, RecordReader};
+/// let file = "I023638FXA3941ENL\nB0941145152265N00032642WA0011500115012345\n";
+/// let mut reader = RecordReader::new(file).capture_extension_defs();
+/// while let Some(record) = reader.next() {
+///     if let Record::B(fix) = record.unwrap() {
+///         let i_record = reader.current_i_record().unwrap();
+///         assert_eq!(fix.extensions(&i_record.extensions).get("ENL"), Some("345"));
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RecordReader<'a> {
+    text: &'a str,
+    lines: SplitLines<'a>,
+    line_number: usize,
+    line_offset: usize,
+    capture: bool,
+    i_record: Option<IRecord<'a>>,
+    j_record: Option<JRecord<'a>>,
+}
+
+impl<'a> RecordReader<'a> {
+    pub fn new(text: &'a str) -> Self {
+        RecordReader {
+            text,
+            lines: split_lines(text),
+            line_number: 0,
+            line_offset: 0,
+            capture: false,
+            i_record: None,
+            j_record: None,
+        }
+    }
+
+    /// Remember the first I and J records read, for use with `current_i_record` and
+    /// `current_j_record`.
+    pub fn capture_extension_defs(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// The first I record read so far, if capturing extension definitions.
+    pub fn current_i_record(&self) -> Option<&IRecord<'a>> {
+        self.i_record.as_ref()
+    }
+
+    /// The first J record read so far, if capturing extension definitions.
+    pub fn current_j_record(&self) -> Option<&JRecord<'a>> {
+        self.j_record.as_ref()
+    }
+
+    /// The 1-based number of the line most recently read, or 0 if nothing has been read yet.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Yield the byte offset of the start of each line within the text passed to `new` alongside
+    /// its record, in the same way as `str::char_indices`.
+    ///
+    /// The offsets can be used to slice the original text, whichever line endings it uses.
+    ///
+    /// ```
+    /// # use igc::records::RecordReader;
+    /// let file = "HFDTE140718\r\nLFoo\r\n";
+    /// let offsets = RecordReader::new(file)
+    ///     .byte_indices()
+    ///     .map(|(offset, _)| offset)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(offsets, vec![0, 13]);
+    /// assert_eq!(&file[13..17], "LFoo");
+    /// ```
+    pub fn byte_indices(self) -> ByteIndices<'a> {
+        ByteIndices { reader: self }
+    }
+
+    /// Read the next record, as with `next`, but attach the line number to any error.
+    ///
+    /// ```
+    /// # use igc::records::RecordReader;
+    /// let mut reader = RecordReader::new("HFDTE140718\nB123\n");
+    /// assert!(reader.next_located().unwrap().is_ok());
+    /// let error = reader.next_located().unwrap().unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "line 2: Too short: expected at least 35 bytes, found 4"
+    /// );
+    /// ```
+    pub fn next_located(&mut self) -> Option<Result<Record<'a>, ParseErrorAt>> {
+        let record = self.next()?;
+        let line = self.line_number;
+        Some(record.map_err(|source| ParseErrorAt { line, source }))
+    }
+}
+
+impl<'a> Iterator for RecordReader<'a> {
+    type Item = Result<Record<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        let record = Record::parse_line(line);
+        self.line_number += 1;
+        // Every line is a slice of the original text
+        self.line_offset = line.as_ptr() as usize - self.text.as_ptr() as usize;
+
+        if self.capture {
+            match &record {
+                Ok(Record::I(rec)) if self.i_record.is_none() => {
+                    self.i_record = Some(rec.clone())
+                }
+                Ok(Record::J(rec)) if self.j_record.is_none() => {
+                    self.j_record = Some(rec.clone())
+                }
+                _ => {}
+            }
+        }
+
+        Some(record)
+    }
+}
+
+/// An iterator over the records read by a `RecordReader`, along with the byte offset of the start
+/// of each line.
+///
+/// Created by `RecordReader::byte_indices`.
+#[derive(Clone, Debug)]
+pub struct ByteIndices<'a> {
+    reader: RecordReader<'a>,
+}
+
+impl<'a> Iterator for ByteIndices<'a> {
+    type Item = (usize, Result<Record<'a>, ParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.reader.next()?;
+        Some((self.reader.line_offset, record))
+    }
+}
+
+/// Split text into lines ending in any of `\r\n`, `\n` or a lone `\r`.
+///
+/// This differs from `str::lines`, which only recognises the first two. Files written by old
+/// software may end their lines with a bare carriage return, and files which have been passed
+/// between systems can end up with a mixture of all three. As with `str::lines`, a line ending
+/// at the very end of the text doesn't start an extra empty line.
+///
+/// ```
+/// # use igc::records::split_lines;
+/// let lines = split_lines("LFoo\r\nLBar\rLBaz\nLQux").collect::<Vec<_>>();
+/// assert_eq!(lines, vec!["LFoo", "LBar", "LBaz", "LQux"]);
+/// ```
+pub fn split_lines(text: &str) -> SplitLines<'_> {
+    SplitLines { rest: text }
+}
+
+/// An iterator over the lines of some text, created by `split_lines`.
+#[derive(Clone, Debug)]
+pub struct SplitLines<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for SplitLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let (line, rest) = match self.rest.find(|c: char| c == '\r' || c == '\n') {
+            Some(end) if self.rest[end..].starts_with("\r\n") => {
+                (&self.rest[..end], &self.rest[end + 2..])
+            }
+            Some(end) => (&self.rest[..end], &self.rest[end + 1..]),
+            None => (self.rest, ""),
+        };
+        self.rest = rest;
+        Some(line)
+    }
+}
+
+/// Iterate over the valid fixes in the text of a whole IGC file.
+///
+/// Only B records with a valid fix are yielded. Every other record is skipped, and so is any line
+/// which fails to parse: the errors are silently dropped. Use `try_fixes` to see them.
+///
+/// ```
+/// # use igc::records::fixes;
+/// let file = "HFDTE140718\n\
+///             B0941145152265N00032642WA0011500115\n\
+///             B0941155152265N00032642WV0011500115\n\
+///             B094116garbage\n";
+/// let times = fixes(file).map(|fix| fix.timestamp.to_string()).collect::<Vec<_>>();
+/// assert_eq!(times, vec!["094114"]);
+/// ```
+pub fn fixes<'a>(text: &'a str) -> impl Iterator<Item = BRecord<'a>> {
+    try_fixes(text).filter_map(Result::ok)
+}
+
+/// Iterate over the valid fixes in the text of a whole IGC file, along with any parse errors.
+///
+/// As with `fixes`, records other than B records with a valid fix are skipped. A line which fails
+/// to parse yields its error, whatever kind of record it was meant to be.
+pub fn try_fixes<'a>(
+    text: &'a str,
+) -> impl Iterator<Item = Result<BRecord<'a>, ParseError>> {
+    RecordReader::new(text).filter_map(|record| match record {
+        Ok(Record::B(fix)) => match fix.fix_valid {
+            FixValid::Valid => Some(Ok(fix)),
+            FixValid::NavWarning => None,
+        },
+        Ok(_) => None,
+        Err(error) => Some(Err(error)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "AXXXABC\r\n\
+                        I023638FXA3941ENL\r\n\
+                        J010812HDT\r\n\
+                        B0941145152265N00032642WA0011500115012345\r\n\
+                        K095214FooTheBar\r\n\
+                        I013638FOO\r\n";
+
+    #[test]
+    fn reads_every_line() {
+        let records = RecordReader::new(FILE)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 6);
+        match records[3] {
+            Record::B(_) => {}
+            _ => panic!("expected a B record"),
+        }
+    }
+
+    #[test]
+    fn captures_first_extension_defs() {
+        let mut reader = RecordReader::new(FILE).capture_extension_defs();
+        assert!(reader.current_i_record().is_none());
+
+        for record in &mut reader {
+            record.unwrap();
+        }
+
+        let i_record = reader.current_i_record().unwrap();
+        assert_eq!(i_record.num_extensions, 2);
+        assert_eq!(i_record.extensions[1].mnemonic, "ENL");
+        assert_eq!(
+            reader.current_j_record().unwrap().extensions[0].mnemonic,
+            "HDT"
+        );
+    }
+
+    #[test]
+    fn no_capture_by_default() {
+        let mut reader = RecordReader::new(FILE);
+        for record in &mut reader {
+            record.unwrap();
+        }
+
+        assert!(reader.current_i_record().is_none());
+        assert!(reader.current_j_record().is_none());
+    }
+
+    #[test]
+    fn located_errors() {
+        let mut reader = RecordReader::new(FILE);
+        assert_eq!(reader.line_number(), 0);
+        for _ in 0..6 {
+            reader.next_located().unwrap().unwrap();
+        }
+        assert_eq!(reader.line_number(), 6);
+        assert!(reader.next_located().is_none());
+
+        let mut reader = RecordReader::new("LFoo\n\nB123");
+        reader.next_located().unwrap().unwrap();
+
+        let error = reader.next_located().unwrap().unwrap_err();
+        assert_eq!(error.line, 2);
+        match error.source {
+            ParseError::SyntaxError => {}
+            _ => panic!("unexpected error {:?}", error.source),
+        }
+
+        let error = reader.next_located().unwrap().unwrap_err();
+        assert_eq!(error.line, 3);
+        #[cfg(feature = "std")]
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn byte_indices_slice_original_text() {
+        for text in &[FILE, "LFoo\n\nB123\nHFDTE140718"] {
+            let mut count = 0;
+            for ((offset, record), line) in
+                RecordReader::new(text).byte_indices().zip(text.lines())
+            {
+                assert_eq!(&text[offset..offset + line.len()], line);
+                if let Ok(record) = record {
+                    assert_eq!(record, Record::parse_line(line).unwrap());
+                }
+                count += 1;
+            }
+            assert_eq!(count, text.lines().count());
+        }
+
+        let offsets = RecordReader::new(FILE)
+            .byte_indices()
+            .map(|(offset, _)| offset)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 9, 28, 40, 83, 101]);
+    }
+
+    #[test]
+    fn mixed_line_endings() {
+        let text = "HFDTE140718\r\n\
+                    B0941145152265N00032642WA0011500115\r\
+                    LFoo\n\
+                    B0941155152265N00032642WA0011500115\r\n\
+                    LBar\r";
+        let records = RecordReader::new(text)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 5);
+        match &records[4] {
+            Record::L(rec) => assert_eq!(rec.log_string, "Bar"),
+            other => panic!("unexpected record {:?}", other),
+        }
+
+        let offsets = RecordReader::new(text)
+            .byte_indices()
+            .map(|(offset, _)| offset)
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![0, 13, 49, 54, 91]);
+    }
+
+    #[test]
+    fn split_lines_like_str_lines() {
+        for text in &["", "\n", "a", "a\n", "a\r\n", "a\n\nb", "a\r\n\r\nb\r\n"] {
+            assert_eq!(
+                split_lines(text).collect::<Vec<_>>(),
+                text.lines().collect::<Vec<_>>()
+            );
+        }
+
+        let lines = split_lines("a\r\rb\n\rc").collect::<Vec<_>>();
+        assert_eq!(lines, vec!["a", "", "b", "", "c"]);
+    }
+
+    #[test]
+    fn fixes_skip_other_records() {
+        let file = "HFDTE140718\n\
+                    B0941145152265N00032642WA0011500115\n\
+                    LFoo\n\
+                    B0941155152265N00032642WV0011500115\n\
+                    B0941165152265N00032642WA0011600116\n";
+        let alts = fixes(file).map(|fix| fix.gps_alt).collect::<Vec<_>>();
+        assert_eq!(alts, vec![115, 116]);
+    }
+
+    #[test]
+    fn try_fixes_yields_errors() {
+        let file = "B0941145152265N00032642WA0011500115\nD1\nB123\n";
+        let fixes = try_fixes(file).collect::<Vec<_>>();
+        assert_eq!(fixes.len(), 3);
+        assert!(fixes[0].is_ok());
+        assert!(fixes[1].is_err());
+        assert!(fixes[2].is_err());
+
+        assert_eq!(super::fixes(file).count(), 1);
+    }
+
+    #[test]
+    fn yields_parse_errors() {
+        let mut reader = RecordReader::new("B123\nHFDTE140718");
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+    }
+}
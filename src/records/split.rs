@@ -0,0 +1,155 @@
+use alloc::vec::Vec;
+
+use crate::records::Record;
+use crate::util::Date;
+
+/// How `split_flights` decides where one flight ends and the next begins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitOn {
+    /// Start a new flight at every A record after the first.
+    ARecord,
+    /// Start a new flight at every DTE header whose date or flight of the day counter differs
+    /// from the previous DTE header. If the DTE header directly follows an A record, the new
+    /// flight starts at the A record instead.
+    FlightDate,
+}
+
+/// Split the records of a file which holds several concatenated flights into one slice per
+/// flight.
+///
+/// Some loggers append each new flight to the same file rather than starting a new one. This is
+/// only a heuristic, chosen by `split_on`: records before the first split point always belong
+/// to the first flight, and a file with no split points comes back as a single flight. An empty
+/// collection of records gives no flights at all.
+///
+/// ```
+/// # use igc::records::{split_flights, RecordReader, SplitOn};
+/// let text = "AXXXABC\nHFDTE230718\nB0941145152265N00032642WA0011500115\n\
+///             AXXXABC\nHFDTE240718\nB1012005152265N00032642WA0011500115\n";
+/// let records = RecordReader::new(text).collect::<Result<Vec<_>, _>>().unwrap();
+/// let flights = split_flights(&records, SplitOn::ARecord);
+/// assert_eq!(flights.len(), 2);
+/// assert_eq!(flights[1].len(), 3);
+/// ```
+pub fn split_flights<'r, 'a>(
+    records: &'r [Record<'a>],
+    split_on: SplitOn,
+) -> Vec<&'r [Record<'a>]> {
+    let mut starts = Vec::new();
+    let mut seen_a_record = false;
+    let mut last_date: Option<(Option<Date>, Option<u8>)> = None;
+
+    for (index, record) in records.iter().enumerate() {
+        match (split_on, record) {
+            (SplitOn::ARecord, Record::A(_)) => {
+                if seen_a_record {
+                    starts.push(index);
+                }
+                seen_a_record = true;
+            }
+            (SplitOn::FlightDate, Record::H(header)) if header.mnemonic == "DTE" => {
+                let date = (header.as_flight_date(), header.flight_of_day());
+                match last_date.replace(date) {
+                    Some(previous) if previous != date => {
+                        let start = match index.checked_sub(1).map(|i| &records[i]) {
+                            Some(Record::A(_)) => index - 1,
+                            _ => index,
+                        };
+                        starts.push(start);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut flights = Vec::with_capacity(starts.len() + 1);
+    let mut current = 0;
+    for start in starts {
+        if start > current {
+            flights.push(&records[current..start]);
+            current = start;
+        }
+    }
+    if current < records.len() {
+        flights.push(&records[current..]);
+    }
+    flights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::parse_records;
+
+    const TWO_FLIGHTS: &str = "AXXXABCFLIGHT:1\n\
+                               HFDTEDATE:230718,01\n\
+                               HFPLTPILOTINCHARGE:Steve Lynn\n\
+                               B0941145152265N00032642WA0011500115\n\
+                               B0941155152265N00032642WA0011500115\n\
+                               AXXXABCFLIGHT:2\n\
+                               HFDTEDATE:230718,02\n\
+                               HFPLTPILOTINCHARGE:Steve Lynn\n\
+                               B1412005152265N00032642WA0011500115\n";
+
+    fn first_fix_times(flights: &[&[Record]]) -> Vec<String> {
+        flights
+            .iter()
+            .map(|flight| {
+                flight
+                    .iter()
+                    .find_map(|record| match record {
+                        Record::B(fix) => Some(fix.timestamp.to_string()),
+                        _ => None,
+                    })
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_on_a_record() {
+        let records = parse_records(TWO_FLIGHTS);
+        let flights = split_flights(&records, SplitOn::ARecord);
+
+        assert_eq!(flights.len(), 2);
+        assert_eq!(flights[0].len(), 5);
+        assert_eq!(flights[1].len(), 4);
+        assert_eq!(first_fix_times(&flights), vec!["094114", "141200"]);
+    }
+
+    #[test]
+    fn split_on_flight_date() {
+        let records = parse_records(TWO_FLIGHTS);
+        let flights = split_flights(&records, SplitOn::FlightDate);
+
+        assert_eq!(flights.len(), 2);
+        match flights[1][0] {
+            Record::A(_) => {}
+            ref other => panic!(
+                "expected the second flight to start with its A record, got {:?}",
+                other
+            ),
+        }
+        assert_eq!(first_fix_times(&flights), vec!["094114", "141200"]);
+    }
+
+    #[test]
+    fn flight_date_ignores_repeated_header() {
+        let records = parse_records(
+            "AXXXABC\n\
+             HFDTE230718\n\
+             B0941145152265N00032642WA0011500115\n\
+             HFDTE230718\n\
+             B0941155152265N00032642WA0011500115\n",
+        );
+        assert_eq!(split_flights(&records, SplitOn::FlightDate).len(), 1);
+        assert_eq!(split_flights(&records, SplitOn::ARecord).len(), 1);
+    }
+
+    #[test]
+    fn no_records() {
+        assert!(split_flights(&[], SplitOn::ARecord).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write as _;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::records::Record;
+use crate::util::{Time, SECONDS_PER_DAY};
+
+fn record_time(record: &Record) -> Option<Time> {
+    match record {
+        Record::B(rec) => Some(rec.timestamp),
+        Record::E(rec) => Some(rec.time),
+        Record::F(rec) => Some(rec.time),
+        Record::K(rec) => Some(rec.time),
+        _ => None,
+    }
+}
+
+/// Put a collection of records into the order they belong in a file.
+///
+/// The A record comes first, followed by the H records, the I and J records, and the C records.
+/// Then come the timestamped records, in time order allowing for the flight crossing midnight,
+/// with any untimed records (such as L records) staying after the timestamped record they
+/// followed. The G records come last, other than any untimed records which already followed them,
+/// as these aren't covered by the security record.
+///
+/// Records in the same place keep their relative order.
+pub fn file_order<'r, 'a>(records: &'r [Record<'a>]) -> Vec<&'r Record<'a>> {
+    let mut start = None;
+    let mut offset = 0;
+    let mut after_security = false;
+
+    let mut keyed = records
+        .iter()
+        .map(|record| {
+            let section = match record {
+                Record::A(_) => 0,
+                Record::H(_) => 1,
+                Record::I(_) | Record::J(_) => 2,
+                Record::CDeclaration(_) | Record::CTurnpoint(_) => 3,
+                Record::G(_) => {
+                    after_security = true;
+                    5
+                }
+                _ => match record_time(record) {
+                    Some(time) => {
+                        let seconds = time.seconds_since_midnight();
+                        let start = *start.get_or_insert(seconds);
+                        offset = (seconds + SECONDS_PER_DAY - start) % SECONDS_PER_DAY;
+                        4
+                    }
+                    None if after_security => 6,
+                    None => 4,
+                },
+            };
+            (section, offset, record)
+        })
+        .collect::<Vec<_>>();
+
+    // A stable sort, so records in the same place keep their relative order
+    keyed.sort_by_key(|&(section, offset, _)| (section, offset));
+    keyed.into_iter().map(|(_, _, record)| record).collect()
+}
+
+/// Write a collection of records out as an IGC file, in the order given by `file_order`, with one
+/// CRLF terminated line per record.
+#[cfg(feature = "std")]
+pub fn write_records<W: io::Write>(records: &[Record], out: &mut W) -> io::Result<()> {
+    for record in file_order(records) {
+        write!(out, "{}\r\n", record)?;
+    }
+    Ok(())
+}
+
+/// Format a collection of records as the text of an IGC file, as with `write_records`.
+pub fn to_igc_string(records: &[Record]) -> String {
+    let mut text = String::new();
+    for record in file_order(records) {
+        // Writing to a String can't fail
+        let _ = write!(text, "{}\r\n", record);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::parse_records;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn example_round_trip() {
+        let records = parse_records(include_str!("../../examples/example.igc"));
+
+        let mut out = Vec::new();
+        write_records(&records, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert_eq!(text, to_igc_string(&records));
+        assert_eq!(parse_records(&text), records);
+    }
+
+    #[test]
+    fn sorts_into_file_order() {
+        let records = parse_records(
+            "K235958XYZ\n\
+             B0000015152265N00032642WA0011500115\n\
+             LAfter the last fix\n\
+             GABCD\n\
+             HFDTE230718\n\
+             B2359595152265N00032642WA0011500115\n\
+             I013638FXA\n\
+             AXXXABC\n\
+             C5156040N00038120WLBZ-Leighton Buzzard NE\n",
+        );
+
+        assert_eq!(
+            to_igc_string(&records),
+            "AXXXABC\r\n\
+             HFDTE230718\r\n\
+             I013638FXA\r\n\
+             C5156040N00038120WLBZ-Leighton Buzzard NE\r\n\
+             K235958XYZ\r\n\
+             B2359595152265N00032642WA0011500115\r\n\
+             B0000015152265N00032642WA0011500115\r\n\
+             LAfter the last fix\r\n\
+             GABCD\r\n"
+        );
+    }
+
+    #[test]
+    fn keeps_trailing_records_after_security() {
+        let records =
+            parse_records("B0941145152265N00032642WA0011500115\nGABCD\nLTrailing\n");
+        let ordered = file_order(&records);
+        assert_eq!(ordered, records.iter().collect::<Vec<_>>());
+    }
+}
@@ -1,11 +1,11 @@
+use core::{convert::TryFrom, fmt, ops::Sub, str::FromStr};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, str::FromStr};
 
 use crate::util::ParseError;
 
 /// Enumeration of cardinal directions
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
@@ -31,8 +31,46 @@ impl fmt::Display for Compass {
     }
 }
 
+impl Compass {
+    /// Whether this is a direction of latitude (north or south) rather than longitude.
+    pub fn is_latitude_axis(&self) -> bool {
+        match self {
+            Compass::North | Compass::South => true,
+            Compass::East | Compass::West => false,
+        }
+    }
+}
+
+impl TryFrom<char> for Compass {
+    type Error = ParseError;
+
+    /// Parse one of the letters 'N', 'S', 'E' or 'W'.
+    fn try_from(letter: char) -> Result<Self, ParseError> {
+        match letter {
+            'N' => Ok(Compass::North),
+            'S' => Ok(Compass::South),
+            'E' => Ok(Compass::East),
+            'W' => Ok(Compass::West),
+            _ => Err(ParseError::SyntaxError),
+        }
+    }
+}
+
+impl FromStr for Compass {
+    type Err = ParseError;
+
+    /// Parse a single letter string, as with `Compass::try_from`.
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) => Compass::try_from(letter),
+            _ => Err(ParseError::SyntaxError),
+        }
+    }
+}
+
 /// Represents a latitude OR longitude, closely representing the form used in IGC files.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RawCoord {
     pub degrees: u8,             // in range (0, 90) for lat, (0, 180) for lon
@@ -62,7 +100,61 @@ impl From<RawCoord> for f64 {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+impl RawCoord {
+    /// Break this coordinate down into whole degrees and decimal minutes, as written in IGC
+    /// files, along with its hemisphere.
+    ///
+    /// ```
+    /// # use igc::util::{Compass, RawLatitude};
+    /// let lat = "5152265N".parse::<RawLatitude>().unwrap();
+    /// assert_eq!(lat.0.to_dm(), (51, 52.265, Compass::North));
+    /// ```
+    pub fn to_dm(&self) -> (u16, f64, Compass) {
+        let minutes = f64::from(self.minute_thousandths) / 1000.;
+        (u16::from(self.degrees), minutes, self.sign)
+    }
+
+    /// Break this coordinate down into whole degrees, whole minutes and decimal seconds, along
+    /// with its hemisphere.
+    ///
+    /// ```
+    /// # use igc::util::{Compass, RawLatitude};
+    /// let lat = "5152265N".parse::<RawLatitude>().unwrap();
+    /// let (degrees, minutes, seconds, sign) = lat.0.to_dms();
+    /// assert_eq!((degrees, minutes, sign), (51, 52, Compass::North));
+    /// assert!((seconds - 15.9).abs() < 1e-9);
+    /// ```
+    pub fn to_dms(&self) -> (u16, u8, f64, Compass) {
+        let minutes = (self.minute_thousandths / 1000) as u8;
+        let seconds = f64::from(self.minute_thousandths % 1000) * 60. / 1000.;
+        (u16::from(self.degrees), minutes, seconds, self.sign)
+    }
+
+    /// Round a value in signed decimal degrees to the nearest representable coordinate.
+    ///
+    /// The magnitude of `value` must be at most 180 degrees. Minutes are rounded as a whole
+    /// with the degrees, so that 59.9995 minutes carries over into the next degree rather than
+    /// becoming 60000 minute thousandths. Anything which rounds to zero, including `-0.0`, is
+    /// given the positive sign.
+    fn from_degrees(value: f64, positive: Compass, negative: Compass) -> Self {
+        let magnitude = if value < 0. { -value } else { value };
+        debug_assert!(magnitude <= 180.);
+
+        // Adding a half before truncating rounds to the nearest, without needing std.
+        let minute_thousandths = (magnitude * 60_000. + 0.5) as u32;
+        RawCoord {
+            degrees: (minute_thousandths / 60_000) as u8,
+            minute_thousandths: (minute_thousandths % 60_000) as u16,
+            sign: if value < 0. && minute_thousandths > 0 {
+                negative
+            } else {
+                positive
+            },
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RawLatitude(pub RawCoord);
 
@@ -70,7 +162,7 @@ impl RawLatitude {
     pub fn new(degrees: u8, minute_thousandths: u16, sign: Compass) -> Self {
         assert!(degrees <= 90);
         assert!(minute_thousandths < 60_000);
-        assert!(sign == Compass::North || sign == Compass::South);
+        assert!(sign.is_latitude_axis());
 
         RawLatitude(RawCoord {
             degrees,
@@ -78,6 +170,29 @@ impl RawLatitude {
             sign,
         })
     }
+
+    /// Convert from signed decimal degrees, positive being north, rounding to the nearest
+    /// thousandth of a minute.
+    ///
+    /// Returns `NumberOutOfRange` for anything beyond the poles, or which isn't a number.
+    ///
+    /// ```
+    /// # use igc::util::{Compass, RawLatitude};
+    /// let lat = RawLatitude::from_degrees(-51.871083).unwrap();
+    /// assert_eq!(lat, RawLatitude::new(51, 52_265, Compass::South));
+    /// ```
+    pub fn from_degrees(value: f64) -> Result<Self, ParseError> {
+        // Written so that NaN fails the check too.
+        if !(-90. ..=90.).contains(&value) {
+            return Err(ParseError::NumberOutOfRange);
+        }
+
+        Ok(RawLatitude(RawCoord::from_degrees(
+            value,
+            Compass::North,
+            Compass::South,
+        )))
+    }
 }
 
 impl FromStr for RawLatitude {
@@ -85,23 +200,25 @@ impl FromStr for RawLatitude {
 
     /// Parse a latitude string of the form "DDMMMMMS"
     fn from_str(lat_string: &str) -> Result<Self, ParseError> {
-        assert_eq!(
-            lat_string.len(),
-            8,
-            "Raw latitude strings are 8 characters long"
-        );
-
+        if lat_string.len() < 8 {
+            return Err(ParseError::TooShort {
+                expected: 8,
+                got: lat_string.len(),
+            });
+        }
+        if lat_string.len() != 8 {
+            return Err(ParseError::SyntaxError);
+        }
         if !lat_string.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
         }
 
         let degrees = lat_string[0..2].parse::<u8>()?;
         let minute_thousandths = lat_string[2..7].parse::<u16>()?;
-        let sign = match &lat_string[7..8] {
-            "N" => Compass::North,
-            "S" => Compass::South,
-            _ => return Err(ParseError::SyntaxError),
-        };
+        let sign = lat_string[7..8].parse::<Compass>()?;
+        if !sign.is_latitude_axis() {
+            return Err(ParseError::SyntaxError);
+        }
 
         if degrees > 90 || minute_thousandths > 60000 {
             Err(ParseError::NumberOutOfRange)
@@ -137,7 +254,24 @@ impl From<RawLatitude> for f64 {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The signed difference in decimal degrees, positive if `self` is further north.
+impl Sub for RawLatitude {
+    type Output = f64;
+
+    fn sub(self, other: RawLatitude) -> f64 {
+        f64::from(self) - f64::from(other)
+    }
+}
+
+impl Sub<&RawLatitude> for &RawLatitude {
+    type Output = f64;
+
+    fn sub(self, other: &RawLatitude) -> f64 {
+        *self - *other
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RawLongitude(pub RawCoord);
 
@@ -145,7 +279,7 @@ impl RawLongitude {
     pub fn new(degrees: u8, minute_thousandths: u16, sign: Compass) -> Self {
         assert!(degrees <= 180);
         assert!(minute_thousandths < 60_000);
-        assert!(sign == Compass::East || sign == Compass::West);
+        assert!(!sign.is_latitude_axis());
 
         RawLongitude(RawCoord {
             degrees,
@@ -153,6 +287,53 @@ impl RawLongitude {
             sign,
         })
     }
+
+    /// Convert from signed decimal degrees, positive being east, rounding to the nearest
+    /// thousandth of a minute.
+    ///
+    /// Returns `NumberOutOfRange` for anything beyond the antimeridian, or which isn't a number.
+    /// Both `-0.0` and values which round to zero come out as east.
+    ///
+    /// ```
+    /// # use igc::util::{Compass, RawLongitude};
+    /// let lon = RawLongitude::from_degrees(-0.544).unwrap();
+    /// assert_eq!(lon, RawLongitude::new(0, 32_640, Compass::West));
+    /// ```
+    pub fn from_degrees(value: f64) -> Result<Self, ParseError> {
+        // Written so that NaN fails the check too.
+        if !(-180. ..=180.).contains(&value) {
+            return Err(ParseError::NumberOutOfRange);
+        }
+
+        Ok(RawLongitude(RawCoord::from_degrees(
+            value,
+            Compass::East,
+            Compass::West,
+        )))
+    }
+
+    /// The signed difference in decimal degrees from `other` to `self` going the short way
+    /// round, which may cross the antimeridian. Positive means `self` is to the east.
+    ///
+    /// The result is in the range [-180, 180].
+    ///
+    /// ```
+    /// # use igc::util::{Compass, RawLongitude};
+    /// let east = RawLongitude::new(179, 0, Compass::East);
+    /// let west = RawLongitude::new(179, 0, Compass::West);
+    /// assert_eq!(&east - &west, 358.);
+    /// assert_eq!(east.shortest_delta(&west), -2.);
+    /// ```
+    pub fn shortest_delta(&self, other: &RawLongitude) -> f64 {
+        let delta = self - other;
+        if delta > 180. {
+            delta - 360.
+        } else if delta < -180. {
+            delta + 360.
+        } else {
+            delta
+        }
+    }
 }
 
 impl FromStr for RawLongitude {
@@ -160,23 +341,25 @@ impl FromStr for RawLongitude {
 
     /// Parse a longitude string of the form "DDDMMMMMW"
     fn from_str(lon_string: &str) -> Result<Self, ParseError> {
-        assert_eq!(
-            lon_string.len(),
-            9,
-            "Raw longitude strings are 9 characters long"
-        );
-
+        if lon_string.len() < 9 {
+            return Err(ParseError::TooShort {
+                expected: 9,
+                got: lon_string.len(),
+            });
+        }
+        if lon_string.len() != 9 {
+            return Err(ParseError::SyntaxError);
+        }
         if !lon_string.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
         }
 
         let degrees = lon_string[0..3].parse::<u8>()?;
         let minute_thousandths = lon_string[3..8].parse::<u16>()?;
-        let sign = match &lon_string[8..9] {
-            "E" => Compass::East,
-            "W" => Compass::West,
-            _ => return Err(ParseError::SyntaxError),
-        };
+        let sign = lon_string[8..9].parse::<Compass>()?;
+        if sign.is_latitude_axis() {
+            return Err(ParseError::SyntaxError);
+        }
 
         if degrees > 180 || minute_thousandths > 60000 {
             Err(ParseError::NumberOutOfRange)
@@ -212,20 +395,201 @@ impl From<RawLongitude> for f64 {
     }
 }
 
+/// The signed difference in decimal degrees, positive if `self` is further east.
+///
+/// This doesn't wrap around the antimeridian; see `RawLongitude::shortest_delta` for that.
+impl Sub for RawLongitude {
+    type Output = f64;
+
+    fn sub(self, other: RawLongitude) -> f64 {
+        f64::from(self) - f64::from(other)
+    }
+}
+
+impl Sub<&RawLongitude> for &RawLongitude {
+    type Output = f64;
+
+    fn sub(self, other: &RawLongitude) -> f64 {
+        *self - *other
+    }
+}
+
 /// A raw lat/lon pair.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RawPosition {
     pub lat: RawLatitude,
     pub lon: RawLongitude,
 }
 
+impl RawPosition {
+    /// Mean radius of the earth in meters, as used for great circle calculations.
+    pub const EARTH_RADIUS_M: f64 = 6_371_000.;
+
+    pub fn new(lat: RawLatitude, lon: RawLongitude) -> Self {
+        RawPosition { lat, lon }
+    }
+
+    /// Convert from signed decimal degrees, positive being north and east, rounding each
+    /// coordinate to the nearest thousandth of a minute.
+    ///
+    /// Returns `NumberOutOfRange` if either coordinate is out of range, as with
+    /// `RawLatitude::from_degrees` and `RawLongitude::from_degrees`.
+    ///
+    /// ```
+    /// # use igc::util::RawPosition;
+    /// let pos = RawPosition::from_degrees(51.871083, -0.544033).unwrap();
+    /// assert_eq!(pos.to_string(), "5152265N00032642W");
+    /// ```
+    pub fn from_degrees(lat: f64, lon: f64) -> Result<Self, ParseError> {
+        Ok(RawPosition {
+            lat: RawLatitude::from_degrees(lat)?,
+            lon: RawLongitude::from_degrees(lon)?,
+        })
+    }
+
+    /// Convert to a `geo` point in decimal degrees, with the longitude as `x` and the latitude as
+    /// `y`.
+    #[cfg(feature = "geo")]
+    pub fn to_geo_point(&self) -> geo_types::Point<f64> {
+        self.into()
+    }
+
+    #[cfg(feature = "std")]
+    fn to_radians(&self) -> (f64, f64) {
+        (
+            f64::from(self.lat).to_radians(),
+            f64::from(self.lon).to_radians(),
+        )
+    }
+
+    /// Great circle distance to another position in meters, using the haversine formula.
+    ///
+    /// ```
+    /// # use igc::util::RawPosition;
+    /// let a = "5156040N00038120W".parse::<RawPosition>().unwrap();
+    /// let b = "5151000N00030000W".parse::<RawPosition>().unwrap();
+    /// assert!((a.haversine_distance_m(&b) - 13_171.6).abs() < 1.);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn haversine_distance_m(&self, other: &RawPosition) -> f64 {
+        let (lat_a, lon_a) = self.to_radians();
+        let (lat_b, lon_b) = other.to_radians();
+
+        let h = ((lat_b - lat_a) / 2.).sin().powi(2)
+            + lat_a.cos() * lat_b.cos() * ((lon_b - lon_a) / 2.).sin().powi(2);
+        2. * Self::EARTH_RADIUS_M * h.sqrt().asin()
+    }
+
+    /// Whether another position is within `tolerance_m` meters of this one, by great circle
+    /// distance.
+    ///
+    /// Unlike `==`, which compares the raw fields exactly, this treats positions which differ by
+    /// less than the tolerance as equal.
+    #[cfg(feature = "std")]
+    pub fn approx_eq(&self, other: &RawPosition, tolerance_m: f64) -> bool {
+        self.haversine_distance_m(other) <= tolerance_m
+    }
+
+    /// Initial great circle bearing to another position, in degrees clockwise from true north in
+    /// the range [0, 360).
+    #[cfg(feature = "std")]
+    pub fn initial_bearing_deg(&self, other: &RawPosition) -> f64 {
+        let (lat_a, lon_a) = self.to_radians();
+        let (lat_b, lon_b) = other.to_radians();
+
+        let y = (lon_b - lon_a).sin() * lat_b.cos();
+        let x =
+            lat_a.cos() * lat_b.sin() - lat_a.sin() * lat_b.cos() * (lon_b - lon_a).cos();
+        (y.atan2(x).to_degrees() + 360.) % 360.
+    }
+
+    /// The position reached by travelling `distance_m` meters along a great circle from this
+    /// position, starting at `bearing_deg` degrees clockwise from true north.
+    ///
+    /// The result is rounded to the nearest representable position.
+    ///
+    /// ```
+    /// # use igc::util::RawPosition;
+    /// let a = "5156040N00038120W".parse::<RawPosition>().unwrap();
+    /// let b = a.offset(90., 1_000.);
+    /// assert!((a.haversine_distance_m(&b) - 1_000.).abs() < 2.);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn offset(&self, bearing_deg: f64, distance_m: f64) -> RawPosition {
+        let (lat, lon) = self.to_radians();
+        let bearing = bearing_deg.to_radians();
+        let angle = distance_m / Self::EARTH_RADIUS_M;
+
+        let dest_lat =
+            (lat.sin() * angle.cos() + lat.cos() * angle.sin() * bearing.cos()).asin();
+        let dest_lon = lon
+            + (bearing.sin() * angle.sin() * lat.cos())
+                .atan2(angle.cos() - lat.sin() * dest_lat.sin());
+
+        // Normalise the longitude back into [-180, 180)
+        let dest_lon = (dest_lon.to_degrees() + 540.) % 360. - 180.;
+
+        RawPosition {
+            lat: RawLatitude(RawCoord::from_degrees(
+                dest_lat.to_degrees(),
+                Compass::North,
+                Compass::South,
+            )),
+            lon: RawLongitude(RawCoord::from_degrees(
+                dest_lon,
+                Compass::East,
+                Compass::West,
+            )),
+        }
+    }
+
+    /// Project this position onto a plane tangent to the earth at `origin`, returning the
+    /// (east, north) offset from the origin in meters.
+    ///
+    /// This is an equirectangular approximation, scaling longitude by the cosine of the origin's
+    /// latitude. It is only accurate over small areas (tens of kilometers) away from the poles,
+    /// but is cheap enough for sector geometry and plotting.
+    ///
+    /// ```
+    /// # use igc::util::RawPosition;
+    /// let origin = "5156040N00038120W".parse::<RawPosition>().unwrap();
+    /// let pos = "5151000N00030000W".parse::<RawPosition>().unwrap();
+    /// let (east, north) = pos.project_local(&origin);
+    /// assert!((east - 9_278.).abs() < 1. && (north + 9_340.).abs() < 1.);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn project_local(&self, origin: &RawPosition) -> (f64, f64) {
+        let (lat, lon) = self.to_radians();
+        let (origin_lat, origin_lon) = origin.to_radians();
+
+        // Take the short way around when straddling the antimeridian
+        let mut d_lon = lon - origin_lon;
+        if d_lon > std::f64::consts::PI {
+            d_lon -= 2. * std::f64::consts::PI;
+        } else if d_lon < -std::f64::consts::PI {
+            d_lon += 2. * std::f64::consts::PI;
+        }
+
+        let east = Self::EARTH_RADIUS_M * d_lon * origin_lat.cos();
+        let north = Self::EARTH_RADIUS_M * (lat - origin_lat);
+        (east, north)
+    }
+}
+
 impl FromStr for RawPosition {
     type Err = ParseError;
 
     fn from_str(pos_string: &str) -> Result<Self, ParseError> {
-        assert_eq!(pos_string.len(), 17);
-
+        if pos_string.len() < 17 {
+            return Err(ParseError::TooShort {
+                expected: 17,
+                got: pos_string.len(),
+            });
+        }
+        if pos_string.len() != 17 {
+            return Err(ParseError::SyntaxError);
+        }
         if !pos_string.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
         }
@@ -237,6 +601,14 @@ impl FromStr for RawPosition {
     }
 }
 
+/// Longitude first, as `x`, in line with the rest of the `geo` ecosystem.
+#[cfg(feature = "geo")]
+impl From<&RawPosition> for geo_types::Point<f64> {
+    fn from(pos: &RawPosition) -> Self {
+        geo_types::Point::new(pos.lon.into(), pos.lat.into())
+    }
+}
+
 impl fmt::Display for RawPosition {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}{}", self.lat, self.lon)
@@ -248,6 +620,74 @@ mod test {
     use super::*;
     use approx::assert_relative_eq;
 
+    #[test]
+    #[cfg(feature = "geo")]
+    fn to_geo_point_is_longitude_first() {
+        let pos = "5130000N00015000W".parse::<RawPosition>().unwrap();
+        let point = pos.to_geo_point();
+        assert_relative_eq!(point.x(), -0.25);
+        assert_relative_eq!(point.y(), 51.5);
+        assert_eq!(geo_types::Point::from(&pos), point);
+    }
+
+    #[test]
+    fn coord_subtraction() {
+        let north = RawLatitude::new(51, 30_000, Compass::North);
+        let south = RawLatitude::new(10, 15_000, Compass::South);
+        let (north_ref, south_ref) = (&north, &south);
+        assert_relative_eq!(north_ref - south_ref, 61.75);
+        assert_relative_eq!(south - north, -61.75);
+
+        let east = RawLongitude::new(1, 0, Compass::East);
+        let west = RawLongitude::new(0, 30_000, Compass::West);
+        let (east_ref, west_ref) = (&east, &west);
+        assert_relative_eq!(east_ref - west_ref, 1.5);
+        assert_relative_eq!(west - east, -1.5);
+        assert_relative_eq!(east.shortest_delta(&west), 1.5);
+    }
+
+    #[test]
+    fn shortest_delta_across_antimeridian() {
+        let east = RawLongitude::new(179, 30_000, Compass::East);
+        let west = RawLongitude::new(179, 45_000, Compass::West);
+
+        assert_relative_eq!(east - west, 359.25);
+        assert_relative_eq!(east.shortest_delta(&west), -0.75);
+        assert_relative_eq!(west.shortest_delta(&east), 0.75);
+
+        let antimeridian = RawLongitude::new(180, 0, Compass::East);
+        let zero = RawLongitude::new(0, 0, Compass::East);
+        assert_relative_eq!(antimeridian.shortest_delta(&zero), 180.);
+        assert_relative_eq!(zero.shortest_delta(&antimeridian), -180.);
+    }
+
+    #[test]
+    fn compass_from_letter() {
+        let cases = [
+            ('N', Compass::North, true),
+            ('S', Compass::South, true),
+            ('E', Compass::East, false),
+            ('W', Compass::West, false),
+        ];
+        for &(letter, compass, latitude) in &cases {
+            assert_eq!(Compass::try_from(letter).unwrap(), compass);
+            assert_eq!(letter.to_string().parse::<Compass>().unwrap(), compass);
+            assert_eq!(compass.to_string(), letter.to_string());
+            assert_eq!(compass.is_latitude_axis(), latitude);
+        }
+
+        assert!(Compass::try_from('n').is_err());
+        assert!(Compass::try_from('X').is_err());
+        assert!("".parse::<Compass>().is_err());
+        assert!("NE".parse::<Compass>().is_err());
+    }
+
+    #[test]
+    fn raw_lat_wrong_axis() {
+        assert!("5152265E".parse::<RawLatitude>().is_err());
+        assert!("05152265N".parse::<RawLongitude>().is_err());
+    }
+
     #[test]
     fn raw_lat_parse() {
         assert_eq!(
@@ -261,6 +701,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn raw_coord_parse_wrong_length() {
+        assert!("5152265".parse::<RawLatitude>().is_err());
+        assert!("5152265NN".parse::<RawLatitude>().is_err());
+        assert!("0515226E".parse::<RawLongitude>().is_err());
+        assert!("05152265EE".parse::<RawLongitude>().is_err());
+        assert!("5152265N0515226W".parse::<RawPosition>().is_err());
+        assert!("5152265N05152265WW".parse::<RawPosition>().is_err());
+
+        assert!(match "0515226E".parse::<RawLongitude>() {
+            Err(ParseError::TooShort {
+                expected: 9,
+                got: 8,
+            }) => true,
+            _ => false,
+        });
+        assert!(match "05152265EE".parse::<RawLongitude>() {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+    }
+
     #[test]
     fn raw_lat_parse_with_invalid_char_boundary() {
         assert!("🌀aaaa".parse::<RawLatitude>().is_err());
@@ -319,6 +781,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn dm_and_dms() {
+        let lat = "5152265N".parse::<RawLatitude>().unwrap();
+        assert_eq!(lat.0.to_dm(), (51, 52.265, Compass::North));
+
+        let (degrees, minutes, seconds, sign) = lat.0.to_dms();
+        assert_eq!((degrees, minutes, sign), (51, 52, Compass::North));
+        assert_relative_eq!(seconds, 15.9);
+
+        let lon = "17959999W".parse::<RawLongitude>().unwrap();
+        assert_eq!(lon.0.to_dm(), (179, 59.999, Compass::West));
+        let (degrees, minutes, seconds, sign) = lon.0.to_dms();
+        assert_eq!((degrees, minutes, sign), (179, 59, Compass::West));
+        assert_relative_eq!(seconds, 59.94);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn raw_position_hash_dedup() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert("5156040N00038120W".parse::<RawPosition>().unwrap());
+        set.insert("5156040N00038120W".parse::<RawPosition>().unwrap());
+        assert_eq!(set.len(), 1);
+
+        set.insert("5156040N00038120E".parse::<RawPosition>().unwrap());
+        assert_eq!(set.len(), 2);
+    }
+
     #[test]
     fn parse_raw_position_with_invalid_char_boundary() {
         assert!("🌀🌀🌀🌀a".parse::<RawPosition>().is_err());
@@ -332,7 +824,169 @@ mod test {
         assert_relative_eq!(f2, -51.87108333333333f64);
     }
 
+    #[test]
+    fn from_degrees() {
+        assert_eq!(
+            RawLatitude::from_degrees(51.871083).unwrap(),
+            RawLatitude::new(51, 52_265, Compass::North)
+        );
+        assert_eq!(
+            RawLongitude::from_degrees(-179.5).unwrap(),
+            RawLongitude::new(179, 30_000, Compass::West)
+        );
+        assert_eq!(
+            RawLatitude::from_degrees(-90.).unwrap(),
+            RawLatitude::new(90, 0, Compass::South)
+        );
+    }
+
+    #[test]
+    fn from_degrees_rounds_minutes_into_degrees() {
+        // Just short of 60 minutes rounds up to a whole degree, rather than to 60.000 minutes.
+        let lat = RawLatitude::from_degrees(51. + 59.9997 / 60.).unwrap();
+        assert_eq!(lat, RawLatitude::new(52, 0, Compass::North));
+
+        let lon = RawLongitude::from_degrees(-(7. + 59.9996 / 60.)).unwrap();
+        assert_eq!(lon, RawLongitude::new(8, 0, Compass::West));
+    }
+
+    #[test]
+    fn from_degrees_zero_is_positive() {
+        for &value in &[0., -0., -1e-9] {
+            assert_eq!(
+                RawLatitude::from_degrees(value).unwrap(),
+                RawLatitude::new(0, 0, Compass::North)
+            );
+            assert_eq!(
+                RawLongitude::from_degrees(value).unwrap(),
+                RawLongitude::new(0, 0, Compass::East)
+            );
+        }
+    }
+
+    #[test]
+    fn raw_position_from_degrees() {
+        let pos = RawPosition::from_degrees(51.934, -0.635333).unwrap();
+        assert_eq!(
+            pos,
+            RawPosition::new(
+                RawLatitude::new(51, 56_040, Compass::North),
+                RawLongitude::new(0, 38_120, Compass::West)
+            )
+        );
+        assert_eq!(pos.to_string().parse::<RawPosition>().unwrap(), pos);
+
+        assert!(RawPosition::from_degrees(91., 0.).is_err());
+        assert!(RawPosition::from_degrees(0., 181.).is_err());
+    }
+
+    #[test]
+    fn from_degrees_out_of_range() {
+        for &value in &[90.001, -91., std::f64::NAN, std::f64::INFINITY] {
+            assert!(RawLatitude::from_degrees(value).is_err());
+        }
+        for &value in &[180.001, -181., std::f64::NAN, std::f64::NEG_INFINITY] {
+            assert!(RawLongitude::from_degrees(value).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn haversine_distance() {
+        let a = "5156040N00038120W".parse::<RawPosition>().unwrap();
+        let b = "5151000N00030000W".parse::<RawPosition>().unwrap();
+
+        let expected = 13_171.6;
+        assert_relative_eq!(a.haversine_distance_m(&b), expected, max_relative = 0.01);
+        assert_relative_eq!(b.haversine_distance_m(&a), expected, max_relative = 0.01);
+        assert_relative_eq!(a.haversine_distance_m(&a), 0.);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn approx_eq() {
+        // Three thousandths of a minute of latitude apart, so about 5.6m
+        let a = "5152265N00032642W".parse::<RawPosition>().unwrap();
+        let b = "5152268N00032642W".parse::<RawPosition>().unwrap();
+
+        assert!(a.approx_eq(&b, 10.));
+        assert!(b.approx_eq(&a, 10.));
+        assert!(!a.approx_eq(&b, 1.));
+        assert!(a.approx_eq(&a, 0.));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn initial_bearing() {
+        let a = "5156040N00038120W".parse::<RawPosition>().unwrap();
+        let b = "5151000N00030000W".parse::<RawPosition>().unwrap();
+        assert_relative_eq!(a.initial_bearing_deg(&b), 135.11, epsilon = 0.01);
+
+        let north = "5256040N00038120W".parse::<RawPosition>().unwrap();
+        assert_relative_eq!(a.initial_bearing_deg(&north), 0., epsilon = 1e-9);
+        assert_relative_eq!(north.initial_bearing_deg(&a), 180., epsilon = 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn project_local() {
+        let origin = "5156040N00038120W".parse::<RawPosition>().unwrap();
+        let pos = "5151000N00030000W".parse::<RawPosition>().unwrap();
+
+        let (east, north) = pos.project_local(&origin);
+        assert_relative_eq!(
+            east.hypot(north),
+            pos.haversine_distance_m(&origin),
+            max_relative = 0.001
+        );
+        assert_relative_eq!(
+            east.atan2(north).to_degrees(),
+            origin.initial_bearing_deg(&pos),
+            epsilon = 0.5
+        );
+        assert_eq!(origin.project_local(&origin), (0., 0.));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn offset() {
+        let a = "5156040N00038120W".parse::<RawPosition>().unwrap();
+        assert_eq!(a.offset(0., 0.), a);
+
+        for &bearing in &[0., 45., 135., 270.] {
+            let b = a.offset(bearing, 5_000.);
+            assert_relative_eq!(a.haversine_distance_m(&b), 5_000., max_relative = 0.001);
+            assert_relative_eq!(a.initial_bearing_deg(&b), bearing, epsilon = 0.1);
+        }
+
+        // One minute of latitude due south, across the equator
+        let north = "0000500N00000000E".parse::<RawPosition>().unwrap();
+        let one_minute = RawPosition::EARTH_RADIUS_M * (1. / 60_f64).to_radians();
+        let south = north.offset(180., one_minute);
+        assert_eq!(south, "0000500S00000000E".parse().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn project_local_across_antimeridian() {
+        let origin = "0000000N17959000E".parse::<RawPosition>().unwrap();
+        let pos = "0000000N17959000W".parse::<RawPosition>().unwrap();
+
+        let (east, north) = pos.project_local(&origin);
+        assert_relative_eq!(east, 2. * 1_853.2, max_relative = 0.001);
+        assert_relative_eq!(north, 0.);
+    }
+
     proptest! {
+        #[test]
+        #[allow(unused_must_use)]
+        fn parse_doesnt_crash(s in "\\PC{0,20}") {
+            s.parse::<RawLatitude>();
+            s.parse::<RawLongitude>();
+            s.parse::<RawPosition>();
+        }
+
         #[test]
         #[allow(unused_must_use)]
         fn raw_lat_parse_back_to_original(d in 0u8..90, m in 0u16..60_000) {
@@ -352,5 +1006,35 @@ mod test {
             let lon = RawLongitude::new(d, m, Compass::West);
             prop_assert_eq!(format!("{}", lon).parse::<RawLongitude>().unwrap(), lon);
         }
+
+        #[test]
+        fn raw_lat_degrees_round_trip(d in 0u8..90, m in 0u16..60_000, south in proptest::bool::ANY) {
+            let sign = if south && (d, m) != (0, 0) { Compass::South } else { Compass::North };
+            let lat = RawLatitude::new(d, m, sign);
+            prop_assert_eq!(RawLatitude::from_degrees(f64::from(lat)).unwrap(), lat);
+        }
+
+        #[test]
+        fn raw_lon_degrees_round_trip(d in 0u8..180, m in 0u16..60_000, west in proptest::bool::ANY) {
+            let sign = if west && (d, m) != (0, 0) { Compass::West } else { Compass::East };
+            let lon = RawLongitude::new(d, m, sign);
+            prop_assert_eq!(RawLongitude::from_degrees(f64::from(lon)).unwrap(), lon);
+        }
+
+        #[test]
+        fn raw_lat_from_degrees_is_nearest(value in -90f64..=90.) {
+            let lat = RawLatitude::from_degrees(value).unwrap();
+            prop_assert!(lat.0.minute_thousandths < 60_000);
+            prop_assert!((f64::from(lat) - value).abs() <= 0.5 / 60_000. + 1e-12);
+        }
+
+        #[test]
+        fn raw_position_degrees_round_trip(lat in -90f64..=90., lon in -180f64..=180.) {
+            let pos = RawPosition::from_degrees(lat, lon).unwrap();
+            let parsed = pos.to_string().parse::<RawPosition>().unwrap();
+            prop_assert_eq!(&parsed, &pos);
+            prop_assert!((f64::from(parsed.lat) - lat).abs() <= 0.5 / 60_000. + 1e-12);
+            prop_assert!((f64::from(parsed.lon) - lon).abs() <= 0.5 / 60_000. + 1e-12);
+        }
     }
 }
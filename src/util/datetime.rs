@@ -1,13 +1,17 @@
+use core::{cmp::Ordering, fmt, str::FromStr};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::{fmt, str::FromStr};
 
 use crate::util::parse_error::ParseError;
 
+pub(crate) const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
 /// Represents a specific time of day with second precision.
 ///
 /// Does not contain any timezone information as the IGC specification mandates UTC everywhere.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///
+/// The default is midnight, 00:00:00.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Time {
     pub seconds: u8,
@@ -18,8 +22,15 @@ pub struct Time {
 impl Time {
     /// Parse a time string of the form "HHMMSS"
     pub fn parse(time_string: &str) -> Result<Self, ParseError> {
-        assert_eq!(time_string.len(), 6);
-
+        if time_string.len() < 6 {
+            return Err(ParseError::TooShort {
+                expected: 6,
+                got: time_string.len(),
+            });
+        }
+        if time_string.len() != 6 {
+            return Err(ParseError::SyntaxError);
+        }
         if !time_string.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
         }
@@ -57,6 +68,43 @@ impl Time {
         let mins: u32 = u32::from(self.hours) * 60 + u32::from(self.minutes);
         mins * 60 + u32::from(self.seconds)
     }
+
+    /// Inverse of `seconds_since_midnight`.
+    ///
+    /// Times of a day or more after midnight wrap around into the following day(s), so the
+    /// result is always in the range [00:00:00, 23:59:59].
+    ///
+    /// ```
+    /// # use igc::util::Time;
+    /// assert_eq!(Time::from_seconds_since_midnight(3723), Time::from_hms(1, 2, 3));
+    /// assert_eq!(Time::from_seconds_since_midnight(86_401), Time::from_hms(0, 0, 1));
+    /// ```
+    pub fn from_seconds_since_midnight(secs: u32) -> Time {
+        let secs = secs % SECONDS_PER_DAY;
+        Time {
+            hours: (secs / 3600) as u8,
+            minutes: (secs / 60 % 60) as u8,
+            seconds: (secs % 60) as u8,
+        }
+    }
+
+    /// Add a (possibly negative) number of seconds to this time, returning `None` if the result
+    /// would fall on a different day.
+    ///
+    /// ```
+    /// # use igc::util::Time;
+    /// let time = Time::from_hms(23, 55, 0);
+    /// assert_eq!(time.checked_add_secs(240), Some(Time::from_hms(23, 59, 0)));
+    /// assert_eq!(time.checked_add_secs(600), None);
+    /// ```
+    pub fn checked_add_secs(&self, secs: i32) -> Option<Time> {
+        let total = i64::from(self.seconds_since_midnight()) + i64::from(secs);
+        if (0..i64::from(SECONDS_PER_DAY)).contains(&total) {
+            Some(Time::from_seconds_since_midnight(total as u32))
+        } else {
+            None
+        }
+    }
 }
 
 impl FromStr for Time {
@@ -67,14 +115,95 @@ impl FromStr for Time {
     }
 }
 
+impl Ord for Time {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.hours, self.minutes, self.seconds).cmp(&(
+            other.hours,
+            other.minutes,
+            other.seconds,
+        ))
+    }
+}
+
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for Time {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:02}{:02}{:02}", self.hours, self.minutes, self.seconds)
     }
 }
 
+/// Whether stepping from one time of day to the next crosses midnight.
+///
+/// IGC timestamps only record the time of day, so a flight crossing UTC midnight jumps from
+/// `235959` back to `000000`. A step backwards of at least half a day is taken to be crossing
+/// midnight, and a smaller one to be time actually going backwards.
+pub(crate) fn crosses_midnight(previous: Time, next: Time) -> bool {
+    let previous = previous.seconds_since_midnight();
+    let next = next.seconds_since_midnight();
+    next < previous && previous - next >= SECONDS_PER_DAY / 2
+}
+
+/// Reconstructs absolute times from a sequence of times of day, such as B record timestamps.
+///
+/// Each time the sequence crosses midnight, going back by at least half a day, the day counter
+/// is rolled forward. A smaller step backwards, such as a logger glitch, is treated as no time
+/// having passed, so the elapsed time never decreases.
+///
+/// ```
+/// # use igc::util::{MonotonicTime, Time};
+/// let mut monotonic = MonotonicTime::new();
+/// assert_eq!(monotonic.push(Time::from_hms(23, 59, 58)), 0);
+/// assert_eq!(monotonic.push(Time::from_hms(0, 0, 1)), 3);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MonotonicTime {
+    start: Option<u32>,
+    previous: Time,
+    days: u32,
+    elapsed: u32,
+}
+
+impl MonotonicTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed in the next time of day, returning the number of seconds elapsed since the first time
+    /// that was fed in.
+    pub fn push(&mut self, time: Time) -> u32 {
+        let seconds = time.seconds_since_midnight();
+        let start = match self.start {
+            Some(start) => {
+                if crosses_midnight(self.previous, time) {
+                    self.days += 1;
+                }
+                start
+            }
+            None => {
+                self.start = Some(seconds);
+                seconds
+            }
+        };
+        self.previous = time;
+
+        let elapsed = (self.days * SECONDS_PER_DAY + seconds).saturating_sub(start);
+        self.elapsed = self.elapsed.max(elapsed);
+        self.elapsed
+    }
+
+    /// The number of times midnight has been crossed so far.
+    pub fn days_elapsed(&self) -> u32 {
+        self.days
+    }
+}
+
 /// Represents a single Gregorian calendar day
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Date {
     /// In the range [1, 31]
@@ -91,8 +220,15 @@ impl Date {
     /// Parses a date string of the form "DDMMYY"
     /// There are not enough digits for the year in this format (bytes are expensive, yo).
     pub fn parse(date_string: &str) -> Result<Self, ParseError> {
-        assert_eq!(date_string.len(), 6);
-
+        if date_string.len() < 6 {
+            return Err(ParseError::TooShort {
+                expected: 6,
+                got: date_string.len(),
+            });
+        }
+        if date_string.len() != 6 {
+            return Err(ParseError::SyntaxError);
+        }
         if !date_string.is_ascii() {
             return Err(ParseError::NonASCIICharacters);
         }
@@ -101,7 +237,7 @@ impl Date {
         let month = date_string[2..4].parse::<u8>()?;
         let year = date_string[4..6].parse::<u8>()?;
 
-        if day > 31 || month > 12 {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(month, year) {
             Err(ParseError::NumberOutOfRange)
         } else {
             Ok(Date { day, month, year })
@@ -110,11 +246,86 @@ impl Date {
 
     /// Helper method to create a Date from a (day, month, year) triplet
     pub fn from_dmy(day: u8, month: u8, year: u8) -> Date {
-        assert!(day >= 1 && day <= 31);
-        assert!(month >= 1 && month <= 12);
+        assert!((1..=31).contains(&day));
+        assert!((1..=12).contains(&month));
         assert!(year <= 99);
+        debug_assert!(day <= days_in_month(month, year));
         Date { day, month, year }
     }
+
+    /// Get the full four digit year of this date, expanded using the given century config.
+    ///
+    /// ```
+    /// # use igc::util::{CenturyConfig, Date};
+    /// assert_eq!(Date::from_dmy(1, 1, 98).full_year(CenturyConfig::default()), 1998);
+    /// assert_eq!(Date::from_dmy(1, 1, 18).full_year(CenturyConfig::default()), 2018);
+    /// ```
+    pub fn full_year(&self, century: CenturyConfig) -> u16 {
+        century.expand_year(self.year)
+    }
+}
+
+/// The number of days in the given month (in the range [1, 12]) of a two digit year.
+///
+/// Two digit years are assumed to be between 1901 and 2099, where every year divisible by four is
+/// a leap year.
+fn days_in_month(month: u8, year: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Defines how two digit years are expanded into full years.
+///
+/// Two digit years below the pivot are taken to be in the 2000s, and those at or above it in the
+/// 1900s. Every API in this crate which needs a full year takes one of these, so that a given
+/// file is interpreted consistently.
+///
+/// The default pivot is 80, as the IGC format didn't exist before then.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CenturyConfig {
+    pub pivot: u8,
+}
+
+impl CenturyConfig {
+    pub const DEFAULT_PIVOT: u8 = 80;
+
+    pub fn new(pivot: u8) -> Self {
+        assert!(pivot <= 100);
+        CenturyConfig { pivot }
+    }
+
+    /// Expand a two digit year (in the range [0, 99]) into a full year.
+    pub fn expand_year(&self, year: u8) -> u16 {
+        assert!(year <= 99);
+        if year < self.pivot {
+            2000 + u16::from(year)
+        } else {
+            1900 + u16::from(year)
+        }
+    }
+}
+
+impl Default for CenturyConfig {
+    fn default() -> Self {
+        CenturyConfig::new(Self::DEFAULT_PIVOT)
+    }
+}
+
+/// The default date is `000000`, which isn't a valid calendar date but the sentinel IGC files
+/// use for "no date", eg. in a C record declaring a flight without a planned date. It won't
+/// parse back with `Date::parse`.
+impl Default for Date {
+    fn default() -> Self {
+        Date {
+            day: 0,
+            month: 0,
+            year: 0,
+        }
+    }
 }
 
 impl FromStr for Date {
@@ -125,6 +336,22 @@ impl FromStr for Date {
     }
 }
 
+/// Dates are ordered by (year, month, day).
+///
+/// As only two digits of the year are stored, this ordering is not century aware: a date in
+/// 1999 (year `99`) compares as later than one in 2001 (year `1`).
+impl Ord for Date {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.year, self.month, self.day).cmp(&(other.year, other.month, other.day))
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for Date {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:02}{:02}{:02}", self.day, self.month, self.year)
@@ -133,7 +360,8 @@ impl fmt::Display for Date {
 
 #[cfg(test)]
 mod test {
-    use super::{Date, Time};
+    use super::{CenturyConfig, Date, MonotonicTime, Time};
+    use crate::util::ParseError;
 
     #[test]
     fn time_parse() {
@@ -144,6 +372,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn time_parse_wrong_length() {
+        assert!(match Time::parse("01234") {
+            Err(ParseError::TooShort {
+                expected: 6,
+                got: 5,
+            }) => true,
+            _ => false,
+        });
+        assert!(match Time::parse("0123456") {
+            Err(ParseError::SyntaxError) => true,
+            _ => false,
+        });
+        assert!(Time::parse("").is_err());
+    }
+
     #[test]
     fn time_parse_with_invalid_char_boundary() {
         assert!(Time::parse("🌀aa").is_err());
@@ -176,23 +420,175 @@ mod test {
         );
     }
 
+    #[test]
+    fn time_checked_add_secs() {
+        let time = Time::from_hms(0, 0, 10);
+        assert_eq!(time.checked_add_secs(-10), Some(Time::from_hms(0, 0, 0)));
+        assert_eq!(time.checked_add_secs(-11), None);
+        assert_eq!(
+            time.checked_add_secs(86_389),
+            Some(Time::from_hms(23, 59, 59))
+        );
+        assert_eq!(time.checked_add_secs(86_390), None);
+        assert_eq!(time.checked_add_secs(i32::max_value()), None);
+        assert_eq!(time.checked_add_secs(i32::min_value()), None);
+    }
+
+    #[test]
+    fn time_ord() {
+        assert!(Time::from_hms(1, 0, 0) > Time::from_hms(0, 59, 59));
+        assert!(Time::from_hms(12, 30, 0) < Time::from_hms(12, 30, 1));
+        assert!(Time::from_hms(12, 31, 0) > Time::from_hms(12, 30, 59));
+
+        let mut times = vec![
+            Time::from_hms(15, 0, 0),
+            Time::from_hms(9, 30, 0),
+            Time::from_hms(9, 29, 59),
+        ];
+        times.sort();
+        assert_eq!(
+            times,
+            vec![
+                Time::from_hms(9, 29, 59),
+                Time::from_hms(9, 30, 0),
+                Time::from_hms(15, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn date_ord() {
+        assert!(Date::from_dmy(1, 1, 19) > Date::from_dmy(31, 12, 18));
+        assert!(Date::from_dmy(1, 2, 18) > Date::from_dmy(31, 1, 18));
+        assert!(Date::from_dmy(2, 1, 18) > Date::from_dmy(1, 1, 18));
+
+        // Not century aware
+        assert!(Date::from_dmy(1, 1, 99) > Date::from_dmy(1, 1, 1));
+    }
+
+    #[test]
+    fn monotonic_time_rollover() {
+        let times = [
+            Time::from_hms(22, 0, 0),
+            Time::from_hms(23, 59, 59),
+            Time::from_hms(0, 0, 1),
+            Time::from_hms(12, 0, 0),
+            Time::from_hms(23, 59, 59),
+            Time::from_hms(0, 0, 0),
+            Time::from_hms(0, 0, 0),
+            Time::from_hms(1, 0, 0),
+        ];
+
+        let mut monotonic = MonotonicTime::new();
+        let offsets = times.iter().map(|t| monotonic.push(*t)).collect::<Vec<_>>();
+
+        let day = 24 * 3600;
+        assert_eq!(
+            offsets,
+            vec![
+                0,
+                2 * 3600 - 1,
+                2 * 3600 + 1,
+                14 * 3600,
+                day + 2 * 3600 - 1,
+                day + 2 * 3600,
+                day + 2 * 3600,
+                day + 3 * 3600,
+            ]
+        );
+        assert_eq!(monotonic.days_elapsed(), 2);
+    }
+
+    #[test]
+    fn monotonic_time_small_step_back() {
+        let times = [
+            Time::from_hms(12, 0, 10),
+            Time::from_hms(12, 0, 5),
+            Time::from_hms(12, 0, 20),
+            Time::from_hms(11, 0, 0),
+            Time::from_hms(12, 0, 21),
+        ];
+
+        let mut monotonic = MonotonicTime::new();
+        let offsets = times.iter().map(|t| monotonic.push(*t)).collect::<Vec<_>>();
+
+        assert_eq!(offsets, vec![0, 0, 10, 10, 11]);
+        assert_eq!(monotonic.days_elapsed(), 0);
+    }
+
     #[test]
     fn date_parse() {
         assert_eq!("010118".parse::<Date>().unwrap(), Date::from_dmy(1, 1, 18));
         assert_eq!("120757".parse::<Date>().unwrap(), Date::from_dmy(12, 7, 57));
     }
 
+    #[test]
+    fn date_parse_day_of_month() {
+        assert_eq!(Date::parse("290220").unwrap(), Date::from_dmy(29, 2, 20));
+        assert_eq!(Date::parse("290200").unwrap(), Date::from_dmy(29, 2, 0));
+        assert_eq!(Date::parse("300418").unwrap(), Date::from_dmy(30, 4, 18));
+        assert_eq!(Date::parse("311218").unwrap(), Date::from_dmy(31, 12, 18));
+
+        assert!(Date::parse("290219").is_err());
+        assert!(Date::parse("310418").is_err());
+        assert!(Date::parse("310218").is_err());
+        assert!(Date::parse("000118").is_err());
+        assert!(Date::parse("010018").is_err());
+        assert!(Date::parse("011318").is_err());
+    }
+
+    #[test]
+    fn date_parse_wrong_length() {
+        assert!(Date::parse("01011").is_err());
+        assert!(Date::parse("0101180").is_err());
+        assert!(Date::parse("").is_err());
+    }
+
     #[test]
     fn date_parse_with_invalid_char_boundary() {
         assert!(Date::parse("🌀aa").is_err());
     }
 
+    #[test]
+    fn century_expansion() {
+        let century = CenturyConfig::default();
+        assert_eq!(century.expand_year(0), 2000);
+        assert_eq!(century.expand_year(79), 2079);
+        assert_eq!(century.expand_year(80), 1980);
+        assert_eq!(century.expand_year(98), 1998);
+
+        let century = CenturyConfig::new(100);
+        assert_eq!(century.expand_year(98), 2098);
+
+        let century = CenturyConfig::new(0);
+        assert_eq!(century.expand_year(18), 1918);
+
+        assert_eq!(
+            Date::from_dmy(3, 4, 98).full_year(CenturyConfig::new(50)),
+            1998
+        );
+    }
+
     #[test]
     fn date_fmt() {
         assert_eq!(format!("{}", Date::from_dmy(5, 10, 18)), "051018");
     }
 
+    #[test]
+    fn defaults() {
+        assert_eq!(Time::default(), Time::from_hms(0, 0, 0));
+        assert_eq!(format!("{}", Date::default()), "000000");
+        assert!(Date::parse("000000").is_err());
+    }
+
     proptest! {
+        #[test]
+        #[allow(unused_must_use)]
+        fn parse_doesnt_crash(s in "\\PC{0,10}") {
+            Time::parse(&s);
+            Date::parse(&s);
+        }
+
         #[test]
         #[allow(unused_must_use)]
         fn time_parse_back_to_original(h in 0u8..24, m in 0u8..60, s in 0u8..60) {
@@ -200,9 +596,46 @@ mod test {
             prop_assert_eq!(Time::parse(&format!("{}", time)).unwrap(), time);
         }
 
+        #[test]
+        fn time_ord_matches_seconds_since_midnight(
+            h1 in 0u8..24, m1 in 0u8..60, s1 in 0u8..60,
+            h2 in 0u8..24, m2 in 0u8..60, s2 in 0u8..60,
+        ) {
+            let t1 = Time::from_hms(h1, m1, s1);
+            let t2 = Time::from_hms(h2, m2, s2);
+            prop_assert_eq!(
+                t1.cmp(&t2),
+                t1.seconds_since_midnight().cmp(&t2.seconds_since_midnight())
+            );
+        }
+
+        #[test]
+        fn time_from_seconds_since_midnight_round_trip(
+            h in 0u8..24, m in 0u8..60, s in 0u8..60,
+        ) {
+            let time = Time::from_hms(h, m, s);
+            prop_assert_eq!(
+                Time::from_seconds_since_midnight(time.seconds_since_midnight()),
+                time
+            );
+        }
+
+        #[test]
+        fn time_checked_add_secs_matches_seconds_since_midnight(
+            h in 0u8..24, m in 0u8..60, s in 0u8..60, secs in -100_000i32..100_000,
+        ) {
+            let time = Time::from_hms(h, m, s);
+            let expected = i64::from(time.seconds_since_midnight()) + i64::from(secs);
+            match time.checked_add_secs(secs) {
+                Some(sum) => prop_assert_eq!(i64::from(sum.seconds_since_midnight()), expected),
+                None => prop_assert!(!(0..86_400).contains(&expected)),
+            }
+        }
+
         #[test]
         #[allow(unused_must_use)]
         fn date_parse_back_to_original(d in 1u8..32, m in 1u8..13, y in 0u8..100) {
+            prop_assume!(d <= super::days_in_month(m, y));
             let date = Date::from_dmy(d, m, y);
             prop_assert_eq!(Date::parse(&format!("{}", date)).unwrap(), date);
         }
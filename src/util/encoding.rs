@@ -0,0 +1,110 @@
+use alloc::borrow::Cow;
+use core::str;
+
+/// The text encoding of the contents of an IGC file.
+///
+/// The specification only allows ASCII, but real files often contain pilot or glider names in
+/// UTF-8 or Latin-1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Utf8,
+    Latin1,
+    Unknown,
+}
+
+/// Detect the encoding of the raw contents of a file.
+///
+/// Strict UTF-8 is tried first, falling back to Latin-1 (ISO 8859-1). Every byte sequence is
+/// technically valid Latin-1, so files containing bytes in the C1 control range (`0x80` to
+/// `0x9F`), which never appear in Latin-1 text, are reported as `Encoding::Unknown`.
+///
+/// ```
+/// # use igc::util::{detect_encoding, Encoding};
+/// assert_eq!(detect_encoding(b"HFPLTPILOT:Joe Bloggs"), Encoding::Ascii);
+/// assert_eq!(detect_encoding("HFPLTPILOT:Zoë".as_bytes()), Encoding::Utf8);
+/// assert_eq!(detect_encoding(b"HFPLTPILOT:Zo\xEB"), Encoding::Latin1);
+/// ```
+pub fn detect_encoding(bytes: &[u8]) -> Encoding {
+    if bytes.is_ascii() {
+        Encoding::Ascii
+    } else if str::from_utf8(bytes).is_ok() {
+        Encoding::Utf8
+    } else if bytes.iter().all(|b| !(0x80..=0x9F).contains(b)) {
+        Encoding::Latin1
+    } else {
+        Encoding::Unknown
+    }
+}
+
+/// Decode the raw contents of a file into text.
+///
+/// Strict UTF-8 is tried first, in which case the contents are borrowed, and otherwise they are
+/// decoded as Latin-1 (ISO 8859-1). As Latin-1 maps every byte directly to the Unicode code point
+/// of the same value, this never fails.
+///
+/// ```
+/// # use igc::util::decode_igc_bytes;
+/// assert_eq!(decode_igc_bytes(b"HFPLTPILOT:Zo\xEB"), "HFPLTPILOT:Zoë");
+/// ```
+pub fn decode_igc_bytes(bytes: &[u8]) -> Cow<'_, str> {
+    match str::from_utf8(bytes) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(_) => Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(detect_encoding(b""), Encoding::Ascii);
+    }
+
+    #[test]
+    fn utf8_c1_range() {
+        // U+0080 is encoded as C2 80 in UTF-8, so must not be mistaken for a C1 control byte
+        assert_eq!(detect_encoding("LXXX\u{80}".as_bytes()), Encoding::Utf8);
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(detect_encoding(b"LXXX\x80\x81"), Encoding::Unknown);
+        assert_eq!(detect_encoding(b"LXXX\xE9\x9F"), Encoding::Unknown);
+    }
+
+    #[test]
+    fn decode_borrows_utf8() {
+        match decode_igc_bytes("LXXXZoë".as_bytes()) {
+            Cow::Borrowed(text) => assert_eq!(text, "LXXXZoë"),
+            Cow::Owned(_) => panic!("valid UTF-8 should be borrowed"),
+        }
+    }
+
+    #[test]
+    fn decode_latin1() {
+        assert_eq!(
+            decode_igc_bytes(b"LXXX\xE9\xFF\x80"),
+            "LXXX\u{e9}\u{ff}\u{80}"
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decode_is_lossless(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..64)) {
+            let text = decode_igc_bytes(&bytes);
+            if str::from_utf8(&bytes).is_err() {
+                let round_trip = text.chars().map(|c| c as u8).collect::<Vec<_>>();
+                prop_assert_eq!(round_trip, bytes);
+            }
+        }
+
+        #[test]
+        fn valid_strings_are_ascii_or_utf8(s in "\\PC*") {
+            let encoding = detect_encoding(s.as_bytes());
+            prop_assert!(encoding == Encoding::Ascii || encoding == Encoding::Utf8);
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use core::fmt;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
@@ -30,6 +31,9 @@ pub enum Manufacturer<'a> {
     Scheffel,
     StreamlineDataInstruments,
     TriadisEngineering,
+    Westerboer,
+    XcSoar,
+    XcTrack,
     Zander,
     UnknownSingle(u8),
     UnknownTriple(&'a str),
@@ -56,6 +60,7 @@ impl<'a> Manufacturer<'a> {
             b'H' => Scheffel,
             b'S' => StreamlineDataInstruments,
             b'T' => TriadisEngineering,
+            b'W' => Westerboer,
             b'Z' => Zander,
             unknown => UnknownSingle(unknown),
         }
@@ -86,6 +91,9 @@ impl<'a> Manufacturer<'a> {
             "SCH" => Scheffel,
             "SDI" => StreamlineDataInstruments,
             "TRI" => TriadisEngineering,
+            "WES" => Westerboer,
+            "XCS" => XcSoar,
+            "XCT" => XcTrack,
             "ZAN" => Zander,
             _ => UnknownTriple(triple),
         }
@@ -112,6 +120,7 @@ impl<'a> Manufacturer<'a> {
             Scheffel => Some(b'H'),
             StreamlineDataInstruments => Some(b'S'),
             TriadisEngineering => Some(b'T'),
+            Westerboer => Some(b'W'),
             Zander => Some(b'Z'),
             UnknownSingle(s) => Some(*s),
             _ => None,
@@ -143,9 +152,167 @@ impl<'a> Manufacturer<'a> {
             Scheffel => Some("SCH"),
             StreamlineDataInstruments => Some("SDI"),
             TriadisEngineering => Some("TRI"),
+            Westerboer => Some("WES"),
+            XcSoar => Some("XCS"),
+            XcTrack => Some("XCT"),
             Zander => Some("ZAN"),
             UnknownTriple(t) => Some(t),
             _ => None,
         }
     }
+
+    /// The human-readable name of this manufacturer, or "Unknown" if it isn't recognised.
+    ///
+    /// ```
+    /// # use igc::util::Manufacturer;
+    /// assert_eq!(Manufacturer::LxNav.name(), "LXNAV");
+    /// assert_eq!(Manufacturer::UnknownTriple("XYZ").name(), "Unknown");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        use self::Manufacturer::*;
+        match self {
+            Aircotec => "Aircotec",
+            CambridgeAeroInstruments => "Cambridge Aero Instruments",
+            ClearNavInstruments => "ClearNav Instruments",
+            DataSwan => "Data Swan",
+            EwAvionics => "EW Avionics",
+            Filser => "Filser",
+            Flarm => "FLARM",
+            Flytech => "Flytech",
+            Garrecht => "Garrecht",
+            ImiGlidingEquipment => "IMI Gliding Equipment",
+            Logstream => "Logstream",
+            LxNavigation => "LX Navigation",
+            LxNav => "LXNAV",
+            Naviter => "Naviter",
+            NewTechnologies => "New Technologies",
+            NielsenKellerman => "Nielsen-Kellerman",
+            Peschges => "Peschges",
+            PressFinishElectronics => "PressFinish Electronics",
+            PrintTechnik => "Print Technik",
+            Scheffel => "Scheffel",
+            StreamlineDataInstruments => "Streamline Data Instruments",
+            TriadisEngineering => "Triadis Engineering",
+            Westerboer => "Westerboer",
+            XcSoar => "XCSoar",
+            XcTrack => "XCTrack",
+            Zander => "Zander",
+            UnknownSingle(_) | UnknownTriple(_) => "Unknown",
+        }
+    }
+}
+
+/// Compares case-insensitively against the three character code or the human-readable name of a
+/// known manufacturer, or against the raw code of an unknown one.
+///
+/// ```
+/// # use igc::util::Manufacturer;
+/// assert!(Manufacturer::LxNav == "LXV");
+/// assert!(Manufacturer::LxNav == "lxnav");
+/// assert!(Manufacturer::UnknownTriple("XYZ") == "xyz");
+/// assert!(Manufacturer::UnknownTriple("XYZ") != "Unknown");
+/// ```
+impl<'a> PartialEq<str> for Manufacturer<'a> {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Manufacturer::UnknownSingle(code) => {
+                other.len() == 1 && other.as_bytes()[0].eq_ignore_ascii_case(code)
+            }
+            Manufacturer::UnknownTriple(code) => code.eq_ignore_ascii_case(other),
+            known => {
+                known.name().eq_ignore_ascii_case(other)
+                    || known
+                        .to_triple_char()
+                        .map_or(false, |code| code.eq_ignore_ascii_case(other))
+            }
+        }
+    }
+}
+
+impl<'a, 's> PartialEq<&'s str> for Manufacturer<'a> {
+    fn eq(&self, other: &&'s str) -> bool {
+        *self == **other
+    }
+}
+
+/// Displays the human-readable name of the manufacturer, or the raw code if it is unknown.
+impl<'a> fmt::Display for Manufacturer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Manufacturer::UnknownSingle(code) => write!(f, "{}", char::from(*code)),
+            Manufacturer::UnknownTriple(code) => write!(f, "{}", code),
+            known => write!(f, "{}", known.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            format!("{}", Manufacturer::CambridgeAeroInstruments),
+            "Cambridge Aero Instruments"
+        );
+        assert_eq!(format!("{}", Manufacturer::parse_single_char(b'X')), "X");
+        assert_eq!(format!("{}", Manufacturer::parse_triple_char("XYZ")), "XYZ");
+    }
+
+    #[test]
+    fn round_trip_new_codes() {
+        for &(triple, manufacturer) in &[
+            ("WES", Manufacturer::Westerboer),
+            ("XCS", Manufacturer::XcSoar),
+            ("XCT", Manufacturer::XcTrack),
+        ] {
+            assert_eq!(Manufacturer::parse_triple_char(triple), manufacturer);
+            assert_eq!(manufacturer.to_triple_char(), Some(triple));
+        }
+
+        assert_eq!(
+            Manufacturer::parse_single_char(b'W'),
+            Manufacturer::Westerboer
+        );
+        assert_eq!(Manufacturer::Westerboer.to_single_char(), Some(b'W'));
+        assert_eq!(Manufacturer::XcSoar.to_single_char(), None);
+        assert_eq!(Manufacturer::XcTrack.to_single_char(), None);
+    }
+
+    #[test]
+    fn compare_with_str() {
+        let lxnav = Manufacturer::parse_triple_char("LXV");
+        assert_eq!(lxnav, "LXV");
+        assert_eq!(lxnav, "lxv");
+        assert_eq!(lxnav, "LXNAV");
+        assert_eq!(
+            Manufacturer::CambridgeAeroInstruments,
+            "cambridge aero instruments"
+        );
+        assert_ne!(lxnav, "LXN");
+        assert_ne!(lxnav, "V");
+        assert_ne!(lxnav, "");
+
+        let unknown = Manufacturer::parse_triple_char("XYZ");
+        assert_eq!(unknown, "XYZ");
+        assert_eq!(unknown, "xyz");
+        assert_ne!(unknown, "Unknown");
+
+        let unknown = Manufacturer::parse_single_char(b'X');
+        assert_eq!(unknown, "X");
+        assert_ne!(unknown, "XX");
+        assert_ne!(unknown, "Unknown");
+    }
+
+    #[test]
+    fn every_known_code_has_a_name() {
+        for code in b'A'..=b'Z' {
+            let manufacturer = Manufacturer::parse_single_char(code);
+            if let Manufacturer::UnknownSingle(_) = manufacturer {
+                continue;
+            }
+            assert_ne!(manufacturer.name(), "Unknown");
+        }
+    }
 }
@@ -3,11 +3,14 @@
 mod coord;
 mod datetime;
 mod display_option;
+mod encoding;
 mod manufacturer;
 mod parse_error;
 
 pub use self::coord::{Compass, RawCoord, RawLatitude, RawLongitude, RawPosition};
-pub use self::datetime::{Date, Time};
+pub(crate) use self::datetime::{crosses_midnight, SECONDS_PER_DAY};
+pub use self::datetime::{CenturyConfig, Date, MonotonicTime, Time};
 pub use self::display_option::DisplayOption;
+pub use self::encoding::{decode_igc_bytes, detect_encoding, Encoding};
 pub use self::manufacturer::Manufacturer;
-pub use self::parse_error::ParseError;
+pub use self::parse_error::{ParseError, ParseErrorAt};
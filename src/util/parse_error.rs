@@ -1,25 +1,92 @@
-use std::io;
-use std::num;
-
-use thiserror::Error;
+use core::{fmt, num, str};
+#[cfg(feature = "std")]
+use std::{error, io};
 
 /// Enumeration of different errors that can occur during parsing
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum ParseError {
-    #[error(transparent)]
-    IOError(#[from] io::Error),
-    #[error(transparent)]
-    Utf8Error(#[from] std::str::Utf8Error),
-    #[error("Syntax error found")]
+    #[cfg(feature = "std")]
+    IOError(io::Error),
+    Utf8Error(str::Utf8Error),
     SyntaxError,
-    #[error("Non-ASCII characters found")]
+    /// The input stops before all of its required fields, eg. a line truncated by the logger.
+    /// Both lengths are in bytes.
+    TooShort {
+        expected: usize,
+        got: usize,
+    },
     NonASCIICharacters,
-    #[error("Invalid number found")]
     NumberOutOfRange,
-    #[error("Invalid extension record found")]
     BadExtension,
-    #[error("Extension record missing")]
     MissingExtension,
+    /// A B record stops after its pressure altitude, as written by some older loggers.
+    MissingGpsAltitude,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ParseError::IOError(error) => fmt::Display::fmt(error, f),
+            ParseError::Utf8Error(error) => fmt::Display::fmt(error, f),
+            ParseError::SyntaxError => f.write_str("Syntax error found"),
+            ParseError::TooShort { expected, got } => write!(
+                f,
+                "Too short: expected at least {} bytes, found {}",
+                expected, got
+            ),
+            ParseError::NonASCIICharacters => f.write_str("Non-ASCII characters found"),
+            ParseError::NumberOutOfRange => f.write_str("Invalid number found"),
+            ParseError::BadExtension => f.write_str("Invalid extension record found"),
+            ParseError::MissingExtension => f.write_str("Extension record missing"),
+            ParseError::MissingGpsAltitude => f.write_str("GPS altitude missing"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        // The wrapped errors are transparent, standing in for their own source.
+        match self {
+            ParseError::IOError(error) => error.source(),
+            ParseError::Utf8Error(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+/// A `ParseError` along with the 1-based number of the line on which it occurred.
+#[derive(Debug)]
+pub struct ParseErrorAt {
+    pub line: usize,
+    pub source: ParseError,
+}
+
+impl fmt::Display for ParseErrorAt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.source)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseErrorAt {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ParseError {
+    fn from(error: io::Error) -> Self {
+        ParseError::IOError(error)
+    }
+}
+
+impl From<str::Utf8Error> for ParseError {
+    fn from(error: str::Utf8Error) -> Self {
+        ParseError::Utf8Error(error)
+    }
 }
 
 impl From<num::ParseIntError> for ParseError {
@@ -27,3 +94,36 @@ impl From<num::ParseIntError> for ParseError {
         ParseError::SyntaxError
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(ParseError::SyntaxError.to_string(), "Syntax error found");
+        assert_eq!(
+            ParseError::TooShort {
+                expected: 35,
+                got: 20
+            }
+            .to_string(),
+            "Too short: expected at least 35 bytes, found 20"
+        );
+
+        let error = ParseErrorAt {
+            line: 3,
+            source: ParseError::MissingExtension,
+        };
+        assert_eq!(error.to_string(), "line 3: Extension record missing");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_error_is_transparent() {
+        let io_error = io::Error::new(io::ErrorKind::Other, "disk on fire");
+        let error = ParseError::from(io_error);
+        assert_eq!(error.to_string(), "disk on fire");
+        assert!(error::Error::source(&error).is_none());
+    }
+}